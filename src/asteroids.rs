@@ -1,5 +1,6 @@
 use crate::bullet::Destructable;
 use crate::health::{Dead, Health};
+use crate::rng::{GameRng, RngStream};
 use crate::sprites::{self, CellSize};
 use crate::{GameState, Layer, assets};
 use avian2d::prelude::*;
@@ -88,6 +89,7 @@ fn spawn_asteroids(
     mut big_cooldown: Local<Option<Timer>>,
     mut small_cooldown: Local<Option<Timer>>,
     spawner: Res<AsteroidSpawner>,
+    mut rng: ResMut<GameRng>,
 ) {
     return;
     //if !spawner.0 {
@@ -104,14 +106,15 @@ fn spawn_asteroids(
     big_cooldown.tick(time.delta());
     small_cooldown.tick(time.delta());
 
-    if big_cooldown.finished() && rand::rng().random_bool(0.001) {
-        let x = rand::rng().random_range(-X..X);
+    let rng = rng.stream(RngStream::Asteroids);
+    if big_cooldown.finished() && rng.random_bool(0.001) {
+        let x = rng.random_range(-X..X);
         commands.spawn((Asteroid::Big, Transform::from_xyz(x, Y, -10.)));
         big_cooldown.reset();
     }
 
-    if small_cooldown.finished() && rand::rng().random_bool(0.005) {
-        let x = rand::rng().random_range(-X..X);
+    if small_cooldown.finished() && rng.random_bool(0.005) {
+        let x = rng.random_range(-X..X);
         commands.spawn((Asteroid::Small, Transform::from_xyz(x, Y, -10.)));
         small_cooldown.reset();
     }
@@ -1,5 +1,6 @@
 use crate::DespawnRestart;
 use crate::bullet::emitter::{BackgroundGattlingEmitter, BulletModifiers, Rate};
+use crate::rng::{GameRng, RngStream};
 use bevy::image::{
     ImageAddressMode, ImageFilterMode, ImageLoaderSettings, ImageSampler, ImageSamplerDescriptor,
 };
@@ -50,6 +51,7 @@ fn spawn_background_emitters(
     emitters: Res<BackgroundEmitters>,
     mut timer: Local<Option<Timer>>,
     mut root: Local<Option<Entity>>,
+    mut rng: ResMut<GameRng>,
 ) {
     if !emitters.0 {
         return;
@@ -71,7 +73,7 @@ fn spawn_background_emitters(
     let timer = timer.get_or_insert_with(|| Timer::from_seconds(0.5, TimerMode::Repeating));
     timer.tick(time.delta());
 
-    let mut rng = rand::rng();
+    let rng = rng.stream(RngStream::Background);
     if timer.just_finished() && rng.random() {
         let z = match rng.random_range(0..=1) {
             0 => LAYER1 - 5.,
@@ -141,6 +143,10 @@ fn update_emitters(
 #[derive(Component)]
 struct Speed(f32);
 
+/// The still image behind every other background layer.
+#[derive(Component)]
+pub struct Backdrop;
+
 fn background(
     mut commands: Commands,
     server: Res<AssetServer>,
@@ -148,6 +154,7 @@ fn background(
     mut mats: ResMut<Assets<ScrollingTexture>>,
 ) {
     commands.spawn((
+        Backdrop,
         Sprite::from_image(server.load("space.png")),
         Transform::from_xyz(0., 0., LAYER5),
     ));
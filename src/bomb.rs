@@ -4,12 +4,14 @@ use crate::effects::{Explosion, SpawnExplosion};
 use crate::pickups::Bomb;
 use crate::player::{AliveContext, Player};
 use crate::points::{self, PointEvent};
+use crate::ship::Ship;
 use crate::text::flash_text;
 use crate::{GameState, RESOLUTION_SCALE};
 use avian2d::prelude::CollidingEntities;
 use bevy::prelude::*;
 use bevy_enhanced_input::prelude::*;
 use bevy_seedling::prelude::*;
+use serde::Deserialize;
 
 const STARTING_BOMBS: usize = 3;
 
@@ -17,7 +19,8 @@ pub struct BombPlugin;
 
 impl Plugin for BombPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(Bombs::new(STARTING_BOMBS))
+        app.add_event::<BombDetonated>()
+            .insert_resource(Bombs::new(STARTING_BOMBS))
             .add_systems(OnEnter(GameState::StartGame), insert_bombs)
             .add_systems(Update, collect_bombs)
             .add_observer(bind)
@@ -36,6 +39,26 @@ impl Bombs {
     pub fn get(&self) -> usize {
         self.0
     }
+
+    pub fn add(&mut self, count: usize) {
+        self.0 += count;
+    }
+}
+
+/// Written when the player uses a bomb.
+#[derive(Event)]
+pub struct BombDetonated {
+    pub position: Vec2,
+}
+
+/// What a ship's bomb clears.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+pub enum BombKind {
+    /// Every enemy bullet on screen.
+    #[default]
+    Clear,
+    /// Enemy bullets within `radius` of the player.
+    Burst { radius: f32 },
 }
 
 fn insert_bombs(mut commands: Commands) {
@@ -61,9 +84,11 @@ fn detonate(
     mut bombs: ResMut<Bombs>,
     mut points: EventWriter<PointEvent>,
     mut explosions: EventWriter<SpawnExplosion>,
+    mut detonated: EventWriter<BombDetonated>,
     bullets: Query<(Entity, &Transform), (With<Bullet>, Without<PlayerBullet>)>,
-    player: Single<&Transform, With<Player>>,
+    player: Single<(&Transform, &Ship), With<Player>>,
 ) {
+    let (player, ship) = player.into_inner();
     if bombs.0 != 0 {
         bombs.0 -= 1;
 
@@ -87,6 +112,7 @@ fn detonate(
         ));
 
         let position = player.translation.xy();
+        detonated.write(BombDetonated { position });
         explosions.write(SpawnExplosion {
             position: position + Vec2::new(15., -15.),
             explosion: Explosion::Big,
@@ -100,12 +126,13 @@ fn detonate(
             explosion: Explosion::Big,
         });
 
-        for (entity, transform) in bullets.iter() {
+        let cleared = bullets.iter().filter(|(_, transform)| match ship.0.bomb {
+            BombKind::Clear => true,
+            BombKind::Burst { radius } => transform.translation.xy().distance(position) <= radius,
+        });
+        for (entity, transform) in cleared {
             commands.entity(entity).despawn();
-            points.write(PointEvent {
-                position: transform.translation.xy(),
-                points: 2,
-            });
+            points.write(PointEvent::new(2, transform.translation.xy()));
             explosions.write(SpawnExplosion {
                 position: transform.translation.xy(),
                 explosion: Explosion::Small,
@@ -0,0 +1,158 @@
+//! Capture bonuses for boss phases, and a record of every attempt that is kept between runs.
+//!
+//! The history is written to `captures.ron`, or the path in `CUCUMBER_CAPTURES`.
+use super::phases::{BossPhases, PhaseEnded, PhaseOutcome};
+use crate::{
+    RESOLUTION_SCALE,
+    bomb::BombDetonated,
+    health::Dead,
+    player::Player,
+    points::{self, PointEvent},
+    text::flash_text,
+};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, io, path::PathBuf};
+
+const DEFAULT_PATH: &str = "captures.ron";
+
+pub struct CapturePlugin;
+
+impl Plugin for CapturePlugin {
+    fn build(&self, app: &mut App) {
+        let path = std::env::var_os("CUCUMBER_CAPTURES")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| DEFAULT_PATH.into());
+
+        app.insert_resource(CaptureHistory::load(path))
+            .add_systems(Update, (fail_captures, pay_captures).chain())
+            .add_observer(fail_captures_on_death);
+    }
+}
+
+/// Attempts and captures of one boss phase.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+pub struct CaptureRecord {
+    pub attempts: u32,
+    pub captures: u32,
+}
+
+/// Every boss phase with a capture bonus the player has attempted, by [`PhaseEnded::key`].
+#[derive(Debug, Default, Resource)]
+pub struct CaptureHistory {
+    /// Where the history is saved. `None` keeps it in memory.
+    path: Option<PathBuf>,
+    records: BTreeMap<String, CaptureRecord>,
+}
+
+impl CaptureHistory {
+    /// Load the history at `path`, starting a new one if there is none.
+    pub fn load(path: PathBuf) -> Self {
+        let records = match std::fs::read_to_string(&path) {
+            Ok(history) => ron::from_str(&history).unwrap_or_else(|err| {
+                error!("could not parse capture history {path:?}: {err}");
+                BTreeMap::new()
+            }),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(err) => {
+                error!("could not read capture history {path:?}: {err}");
+                BTreeMap::new()
+            }
+        };
+
+        Self {
+            path: Some(path),
+            records,
+        }
+    }
+
+    /// A history that is never saved.
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &str) -> CaptureRecord {
+        self.records.get(key).copied().unwrap_or_default()
+    }
+
+    fn record(&mut self, key: &str, captured: bool) {
+        let record = self.records.entry(key.to_string()).or_default();
+        record.attempts += 1;
+        if captured {
+            record.captures += 1;
+        }
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let history = ron::ser::to_string_pretty(&self.records, Default::default())
+            .map_err(io::Error::other)?;
+        std::fs::write(path, history)
+    }
+}
+
+fn fail_captures(mut bombs: EventReader<BombDetonated>, mut bosses: Query<&mut BossPhases>) {
+    if bombs.read().count() > 0 {
+        for mut phases in bosses.iter_mut() {
+            phases.fail_capture();
+        }
+    }
+}
+
+/// Hits that only cost shield or health keep the bonus, dying does not.
+fn fail_captures_on_death(
+    trigger: Trigger<OnAdd, Dead>,
+    player: Query<(), With<Player>>,
+    mut bosses: Query<&mut BossPhases>,
+) {
+    if player.contains(trigger.target()) {
+        for mut phases in bosses.iter_mut() {
+            phases.fail_capture();
+        }
+    }
+}
+
+fn pay_captures(
+    mut commands: Commands,
+    server: Res<AssetServer>,
+    mut reader: EventReader<PhaseEnded>,
+    mut history: ResMut<CaptureHistory>,
+    mut points: EventWriter<PointEvent>,
+) {
+    let mut changed = false;
+
+    for event in reader.read() {
+        if event.capture_bonus == 0 {
+            continue;
+        }
+
+        history.record(&event.key, event.captured);
+        changed = true;
+
+        let text = if event.captured {
+            points.write(PointEvent::new(event.capture_bonus, event.position));
+            "CAPTURED"
+        } else if event.outcome == PhaseOutcome::TimedOut {
+            "TIME OUT"
+        } else {
+            "BONUS FAILED"
+        };
+        flash_text(
+            &mut commands,
+            &server,
+            text,
+            24.,
+            ((event.position + Vec2::Y * 20.) * RESOLUTION_SCALE).extend(points::POINT_TEXT_Z + 2.),
+            points::COLOR,
+        );
+    }
+
+    if changed {
+        if let Err(err) = history.save() {
+            error!("could not save capture history: {err}");
+        }
+    }
+}
@@ -1,9 +1,8 @@
 use crate::bullet::{
-    BulletTimer, Polarity, RedOrb,
-    emitter::{BulletModifiers, EmitterDelay, ORB_SPEED},
+    BulletTimer, Polarity,
+    emitter::{BulletCommands, BulletModifiers, EmitterDelay, ORB_SPEED, Target},
 };
 use crate::float_tween;
-use avian2d::prelude::*;
 use bevy::prelude::*;
 use bevy_tween::{
     combinator::{sequence, tween},
@@ -94,17 +93,19 @@ impl GradiusSpiralEmitter {
                 continue;
             }
 
-            let mut new_transform = transform.compute_transform();
-            new_transform.translation += polarity.to_vec2().extend(0.0) * 10.0;
+            let position = transform.translation().xy() + polarity.to_vec2() * 10.0;
 
-            let bullets = 10;
-            for angle in 0..bullets {
-                let angle = (angle as f32 / bullets as f32) * 2. * std::f32::consts::PI + offset.0;
-                commands.spawn((
-                    RedOrb,
-                    LinearVelocity(Vec2::from_angle(angle) * ORB_SPEED * mods.speed),
-                    new_transform,
-                ));
+            // far too many bullets for entities
+            let mut bullets = BulletCommands::new(
+                &mut commands.reborrow(),
+                mods,
+                Target::dir(Vec2::X),
+                Vec2::splat(ORB_SPEED),
+            );
+            let count = 10;
+            for angle in 0..count {
+                let angle = (angle as f32 / count as f32) * 2. * std::f32::consts::PI + offset.0;
+                bullets.spawn_light(angle, position);
             }
         }
     }
@@ -1,24 +1,29 @@
 use self::emitters::{GradiusSpiralEmitter, SpiralOffsetTween};
 use crate::asteroids::SpawnCluster;
 use crate::auto_collider::ImageCollider;
+use crate::boss::phases::{BossPhase, BossPhases};
+use crate::boss::{Boss, BossDefeated};
 use crate::bullet::Destructable;
 use crate::bullet::emitter::{
     BulletModifiers, EmitterDelay, PulseTime, Rate, SpiralOrbEmitter, Target,
 };
+use crate::bullet::pattern::PatternEmitter;
+use crate::bullet::script::ScriptEmitter;
 use crate::enemy::Enemy;
-use crate::enemy::waller::WallEmitter;
 use crate::health::{Dead, Health};
-use crate::{DespawnRestart, GameState, Layer, RESOLUTION_SCALE};
+use crate::{DespawnRestart, GameState, Layer};
 use avian2d::prelude::CollisionLayers;
 use bevy::prelude::*;
 use bevy_optix::debug::DebugRect;
-use bevy_optix::pixel_perfect::HIGH_RES_LAYER;
 use bevy_tween::BevyTweenRegisterSystems;
 use bevy_tween::tween::apply_component_tween_system;
 
 pub mod emitters;
 
 const HEALTH: f32 = 300.;
+const FLOWER_SCRIPT: &str = "patterns/gradius_flower.script.ron";
+const FLANK_PATTERN: &str = "patterns/gradius_flank.pattern.ron";
+const WALL_PATTERN: &str = "patterns/gradius_wall.pattern.ron";
 
 pub struct GradiusPlugin;
 
@@ -27,29 +32,19 @@ impl Plugin for GradiusPlugin {
         app.add_systems(PreUpdate, GradiusSpiralEmitter::shoot_bullets)
             .add_systems(
                 Update,
-                (
-                    update_phase,
-                    phasea,
-                    phasec,
-                    update_health_display,
-                    kill_boss,
-                )
-                    .run_if(in_state(GameState::Game)),
+                (flip_orb_emitters, kill_boss).run_if(in_state(GameState::Game)),
             )
             .add_tween_systems(apply_component_tween_system::<SpiralOffsetTween>)
-            .add_observer(init_gradius)
-            .add_observer(enter_phasea)
-            .add_observer(exit_phasea)
-            .add_observer(enter_phaseb)
-            .add_observer(exit_phaseb)
-            .add_observer(enter_phasec);
+            .add_observer(init_gradius);
     }
 }
 
 #[derive(Component)]
 #[require(
     Enemy,
-    PhaseA,
+    Boss,
+    Name::new("Gradius"),
+    BossPhases = Gradius::phases(),
     ImageCollider,
     Destructable,
     Health::full(HEALTH),
@@ -59,44 +54,33 @@ impl Plugin for GradiusPlugin {
 )]
 pub struct Gradius;
 
-fn init_gradius(
-    trigger: Trigger<OnAdd, Gradius>,
-    mut commands: Commands,
-    server: Res<AssetServer>,
-) {
+impl Gradius {
+    fn phases() -> BossPhases {
+        BossPhases::new([
+            BossPhase::until_health(2. / 3.).emitters(flower),
+            BossPhase::until_health(1. / 3.)
+                .named("Spiral Sign \"Coiled Walls\"")
+                .time_limit(40.)
+                .capture_bonus(5000)
+                .emitters(spiral_walls),
+            BossPhase::until_health(0.)
+                .named("Orb Sign \"Flipped Flanks\"")
+                .time_limit(50.)
+                .capture_bonus(8000)
+                .emitters(orb_walls),
+        ])
+    }
+}
+
+fn init_gradius(trigger: Trigger<OnAdd, Gradius>, mut commands: Commands) {
     commands
         .entity(trigger.target())
         .insert(DebugRect::from_size(Vec2::new(
             crate::WIDTH / 2.,
             crate::WIDTH / 3.75,
         )));
-    commands.spawn((
-        HealthDisplay,
-        HIGH_RES_LAYER,
-        Text2d::default(),
-        TextFont {
-            font_size: 16.,
-            font: server.load("fonts/joystix.otf"),
-            ..Default::default()
-        },
-        Transform::from_translation(
-            (Vec2::new(0., crate::HEIGHT / 2. - 10.) * RESOLUTION_SCALE).extend(500.),
-        ),
-    ));
 }
 
-#[derive(Default, Component)]
-struct PhaseA;
-
-#[derive(Default, Component)]
-pub struct PhaseB;
-
-#[derive(Default, Component)]
-pub struct PhaseC;
-
-#[derive(Component)]
-struct HealthDisplay;
-
 #[derive(Component)]
 struct FlipOrbEmitters(Timer);
 
@@ -106,51 +90,18 @@ impl FlipOrbEmitters {
     }
 }
 
-fn update_phase(
-    mut commands: Commands,
-    gradius: Single<(Entity, &Health), With<Gradius>>,
-    a: Option<Single<&PhaseA>>,
-    b: Option<Single<&PhaseB>>,
-) {
-    let (entity, health) = gradius.into_inner();
-
-    if a.is_some() {
-        if health.current() <= 2. * health.max() / 3. {
-            commands.entity(entity).remove::<PhaseA>().insert(PhaseB);
-        }
-    } else if b.is_some() {
-        if health.current() <= health.max() / 3. {
-            commands.entity(entity).remove::<PhaseB>().insert(PhaseC);
-        }
-    }
-}
-
-fn enter_phasea(trigger: Trigger<OnAdd, PhaseA>, mut commands: Commands) {
+/// Two spiral orb emitters that swap heights between volleys, flanked by walls.
+fn orb_walls(root: &mut EntityCommands) {
     let orb = SpiralOrbEmitter::new(8, 2.0, 0.2);
     let total_time = orb.total_time();
-    let buck_waves = 4;
-    let buck_shot_dur = 0.2;
-    let buck_wait = total_time - buck_shot_dur * buck_waves as f32;
 
-    commands
-        .entity(trigger.target())
-        .insert(FlipOrbEmitters::new(orb.total_time()))
+    root.insert(FlipOrbEmitters::new(total_time))
         .with_children(|root| {
             root.spawn((orb.clone(), Transform::from_xyz(-40., 0., 0.)));
             root.spawn((orb, Transform::from_xyz(40., 40., 0.)));
 
-            //root.spawn((
-            //    BuckShotEmitter::new(buck_waves, buck_wait, buck_shot_dur),
-            //    Target::NEG_Y,
-            //    BulletModifiers {
-            //        speed: 0.5,
-            //        ..Default::default()
-            //    },
-            //    EmitterDelay::new(total_time / 2.),
-            //));
-
             root.spawn((
-                WallEmitter::default(),
+                PatternEmitter::new(FLANK_PATTERN),
                 Target::dir(Vec2::from_angle(std::f32::consts::PI * 2. * 0.80)),
                 BulletModifiers {
                     speed: 1.25,
@@ -161,7 +112,7 @@ fn enter_phasea(trigger: Trigger<OnAdd, PhaseA>, mut commands: Commands) {
                 Transform::from_xyz(-40., 30., 0.),
             ));
             root.spawn((
-                WallEmitter::default(),
+                PatternEmitter::new(FLANK_PATTERN),
                 Target::dir(Vec2::from_angle(std::f32::consts::PI * 2. * 0.70)),
                 BulletModifiers {
                     speed: 1.25,
@@ -174,158 +125,57 @@ fn enter_phasea(trigger: Trigger<OnAdd, PhaseA>, mut commands: Commands) {
         });
 }
 
-fn phasea(
+fn flip_orb_emitters(
     time: Res<Time>,
-    gradius: Single<(&Children, &mut FlipOrbEmitters), With<PhaseA>>,
+    mut roots: Query<(&Children, &mut FlipOrbEmitters)>,
     mut emitters: Query<&mut Transform, With<SpiralOrbEmitter>>,
 ) {
-    let (children, mut flip_orbs) = gradius.into_inner();
-
-    flip_orbs.0.tick(time.delta());
-    if flip_orbs.0.just_finished() {
-        let mut iter = emitters.iter_many_mut(children.iter());
-        while let Some(mut transform) = iter.fetch_next() {
-            match transform.translation.y {
-                0. => transform.translation.y = 40.,
-                40. => transform.translation.y = 0.,
-                _ => unreachable!(),
+    for (children, mut flip_orbs) in roots.iter_mut() {
+        flip_orbs.0.tick(time.delta());
+        if flip_orbs.0.just_finished() {
+            let mut iter = emitters.iter_many_mut(children.iter());
+            while let Some(mut transform) = iter.fetch_next() {
+                match transform.translation.y {
+                    0. => transform.translation.y = 40.,
+                    40. => transform.translation.y = 0.,
+                    _ => unreachable!(),
+                }
             }
         }
     }
 }
 
-fn exit_phasea(trigger: Trigger<OnRemove, PhaseA>, mut commands: Commands) {
-    commands
-        .entity(trigger.target())
-        .try_remove::<FlipOrbEmitters>()
-        .despawn_related::<Children>();
+/// Rings of orbs that stop and burst into arrows aimed at the player.
+fn flower(root: &mut EntityCommands) {
+    root.with_children(|root| {
+        root.spawn(ScriptEmitter::new(FLOWER_SCRIPT));
+    });
 }
 
-fn enter_phaseb(trigger: Trigger<OnAdd, PhaseB>, mut commands: Commands) {
-    commands.entity(trigger.target()).with_children(|root| {
+/// A spiral with two staggered walls falling straight down.
+fn spiral_walls(root: &mut EntityCommands) {
+    root.with_children(|root| {
         root.spawn(GradiusSpiralEmitter);
+        root.spawn(PatternEmitter::new(WALL_PATTERN));
         root.spawn((
-            WallEmitter {
-                bullets: 15,
-                gap: 20.,
-                bowl: 0.,
-            },
-            Target::NEG_Y,
-            BulletModifiers {
-                rate: Rate::Secs(2.),
-                ..Default::default()
-            },
-        ));
-        root.spawn((
-            WallEmitter {
-                bullets: 15,
-                gap: 20.,
-                bowl: 0.,
-            },
-            Target::NEG_Y,
-            BulletModifiers {
-                rate: Rate::Secs(2.),
-                ..Default::default()
-            },
+            PatternEmitter::new(WALL_PATTERN),
             EmitterDelay::new(1.),
             Transform::from_xyz(10., 0., 0.),
         ));
     });
 }
 
-fn exit_phaseb(trigger: Trigger<OnRemove, PhaseB>, mut commands: Commands) {
-    commands
-        .entity(trigger.target())
-        .despawn_related::<Children>();
-}
-
-fn enter_phasec(trigger: Trigger<OnAdd, PhaseC>, mut commands: Commands) {
-    let orb = SpiralOrbEmitter::new(8, 2.0, 0.2);
-    let total_time = orb.total_time();
-    let buck_waves = 4;
-    let buck_shot_dur = 0.2;
-    let buck_wait = total_time - buck_shot_dur * buck_waves as f32;
-
-    commands
-        .entity(trigger.target())
-        .insert(FlipOrbEmitters::new(orb.total_time()))
-        .with_children(|root| {
-            root.spawn((orb.clone(), Transform::from_xyz(-40., 0., 0.)));
-            root.spawn((orb, Transform::from_xyz(40., 40., 0.)));
-
-            //root.spawn((
-            //    BuckShotEmitter::new(buck_waves, buck_wait, buck_shot_dur),
-            //    Target::NEG_Y,
-            //    BulletModifiers {
-            //        speed: 0.5,
-            //        ..Default::default()
-            //    },
-            //    EmitterDelay::new(total_time / 2.),
-            //));
-
-            root.spawn((
-                WallEmitter::default(),
-                Target::dir(Vec2::from_angle(std::f32::consts::PI * 2. * 0.80)),
-                BulletModifiers {
-                    speed: 1.25,
-                    rate: Rate::Secs(total_time),
-                    ..Default::default()
-                },
-                EmitterDelay::new(total_time / 2.),
-                Transform::from_xyz(-40., 30., 0.),
-            ));
-            root.spawn((
-                WallEmitter::default(),
-                Target::dir(Vec2::from_angle(std::f32::consts::PI * 2. * 0.70)),
-                BulletModifiers {
-                    speed: 1.25,
-                    rate: Rate::Secs(total_time),
-                    ..Default::default()
-                },
-                EmitterDelay::new(total_time / 2.),
-                Transform::from_xyz(40., 30., 0.),
-            ));
-        });
-}
-
-fn phasec(
-    time: Res<Time>,
-    gradius: Single<(&Children, &mut FlipOrbEmitters), With<PhaseC>>,
-    mut emitters: Query<&mut Transform, With<SpiralOrbEmitter>>,
-) {
-    let (children, mut flip_orbs) = gradius.into_inner();
-
-    flip_orbs.0.tick(time.delta());
-    if flip_orbs.0.just_finished() {
-        let mut iter = emitters.iter_many_mut(children.iter());
-        while let Some(mut transform) = iter.fetch_next() {
-            match transform.translation.y {
-                0. => transform.translation.y = 40.,
-                40. => transform.translation.y = 0.,
-                _ => unreachable!(),
-            }
-        }
-    }
-}
-
-fn update_health_display(
-    health: Single<&Health, (With<Gradius>, Changed<Health>)>,
-    mut text: Single<&mut Text2d, With<HealthDisplay>>,
-) {
-    text.0 = format!("Health: {:.2} / {:.2}", health.current(), health.max());
-}
-
 fn kill_boss(
     mut commands: Commands,
     boss: Single<(Entity, &Transform), (With<Gradius>, With<Dead>)>,
-    health: Single<Entity, With<HealthDisplay>>,
     mut writer: EventWriter<SpawnCluster>,
+    mut defeated: EventWriter<BossDefeated>,
 ) {
     let (entity, transform) = boss.into_inner();
     commands.entity(entity).despawn();
-    commands.entity(*health).despawn();
 
     let position = transform.translation.xy();
+    defeated.write(BossDefeated { position });
     writer.write(SpawnCluster {
         parts: 10,
         shield: 10,
@@ -0,0 +1,185 @@
+//! Health bar shown along the top of the screen for every [`Boss`].
+//!
+//! The bar is split into segments, with a marker wherever one of the boss's [`BossPhases`] ends.
+//! Below it are the boss's [`Name`], and the current phase's name and time limit.
+use super::{Boss, phases::BossPhases};
+use crate::{DespawnRestart, GameState, RESOLUTION_SCALE, color::HexColor, health::Health};
+use bevy::{prelude::*, sprite::Anchor};
+use bevy_optix::pixel_perfect::HIGH_RES_LAYER;
+
+const SEGMENTS: usize = 26;
+/// Width of a segment, including the gap after it.
+const SEGMENT_WIDTH: f32 = 4.;
+const BAR_WIDTH: f32 = SEGMENTS as f32 * SEGMENT_WIDTH;
+const BAR_Y: f32 = crate::HEIGHT / 2. - 20.;
+const HUD_Z: f32 = 500.;
+
+/// Seconds the bar takes to drop in and fill up.
+const ENTRANCE_SECS: f32 = 1.;
+
+const FILLED: HexColor = HexColor(0xd41e3c);
+const EMPTY: Color = Color::srgba(1., 1., 1., 0.15);
+
+pub struct BossHudPlugin;
+
+impl Plugin for BossHudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, update_huds.run_if(in_state(GameState::Game)))
+            .add_observer(spawn_hud)
+            .add_observer(despawn_hud);
+    }
+}
+
+#[derive(Component)]
+#[require(DespawnRestart, Visibility)]
+struct BossHud {
+    boss: Entity,
+    entrance: Timer,
+}
+
+#[derive(Component)]
+struct Segment(usize);
+
+#[derive(Component)]
+struct PhaseName;
+
+#[derive(Component)]
+struct PhaseTimer;
+
+fn spawn_hud(
+    trigger: Trigger<OnAdd, Boss>,
+    mut commands: Commands,
+    server: Res<AssetServer>,
+    bosses: Query<(Option<&Name>, Option<&BossPhases>)>,
+) {
+    let Ok((name, phases)) = bosses.get(trigger.target()) else {
+        return;
+    };
+
+    let left = -BAR_WIDTH / 2. * RESOLUTION_SCALE;
+    let font = |size: f32| TextFont {
+        font_size: size,
+        font: server.load("fonts/joystix.otf"),
+        ..Default::default()
+    };
+
+    commands
+        .spawn((
+            BossHud {
+                boss: trigger.target(),
+                entrance: Timer::from_seconds(ENTRANCE_SECS, TimerMode::Once),
+            },
+            Transform::from_xyz(0., crate::HEIGHT * RESOLUTION_SCALE, HUD_Z),
+        ))
+        .with_children(|root| {
+            for i in 0..SEGMENTS {
+                root.spawn((
+                    Segment(i),
+                    HIGH_RES_LAYER,
+                    Sprite {
+                        anchor: Anchor::CenterLeft,
+                        ..Sprite::from_color(
+                            EMPTY,
+                            Vec2::new(SEGMENT_WIDTH - 1., 2.) * RESOLUTION_SCALE,
+                        )
+                    },
+                    Transform::from_xyz(left + i as f32 * SEGMENT_WIDTH * RESOLUTION_SCALE, 0., 0.),
+                ));
+            }
+
+            for fraction in phases.iter().flat_map(|phases| phases.markers()) {
+                root.spawn((
+                    HIGH_RES_LAYER,
+                    Sprite::from_color(Color::WHITE, Vec2::new(1., 4.) * RESOLUTION_SCALE),
+                    Transform::from_xyz(left + fraction * BAR_WIDTH * RESOLUTION_SCALE, 0., 1.),
+                ));
+            }
+
+            root.spawn((
+                HIGH_RES_LAYER,
+                Text2d::new(name.map(Name::as_str).unwrap_or_default()),
+                font(12.),
+                Anchor::TopLeft,
+                Transform::from_xyz(left, -2. * RESOLUTION_SCALE, 0.),
+            ));
+            root.spawn((
+                PhaseName,
+                HIGH_RES_LAYER,
+                Text2d::default(),
+                font(12.),
+                Anchor::TopCenter,
+                Transform::from_xyz(0., -6. * RESOLUTION_SCALE, 0.),
+            ));
+            root.spawn((
+                PhaseTimer,
+                HIGH_RES_LAYER,
+                Text2d::default(),
+                font(12.),
+                Anchor::TopRight,
+                Transform::from_xyz(-left, -2. * RESOLUTION_SCALE, 0.),
+            ));
+        });
+}
+
+fn update_huds(
+    time: Res<Time>,
+    mut huds: Query<(&mut BossHud, &mut Transform, &Children)>,
+    bosses: Query<(&Health, Option<&BossPhases>)>,
+    mut segments: Query<(&Segment, &mut Sprite)>,
+    mut phase_names: Query<&mut Text2d, With<PhaseName>>,
+    mut timers: Query<&mut Text2d, (With<PhaseTimer>, Without<PhaseName>)>,
+) {
+    for (mut hud, mut transform, children) in huds.iter_mut() {
+        let Ok((health, phases)) = bosses.get(hud.boss) else {
+            continue;
+        };
+
+        hud.entrance.tick(time.delta());
+        let entrance = hud.entrance.fraction();
+        let start = crate::HEIGHT * RESOLUTION_SCALE;
+        let end = BAR_Y * RESOLUTION_SCALE;
+        transform.translation.y = start + (end - start) * (entrance * 2.).min(1.);
+
+        // fill up once the bar is in place
+        let shown = health.proportion().min((entrance * 2. - 1.).max(0.));
+        let filled = (shown * SEGMENTS as f32).ceil() as usize;
+        let mut iter = segments.iter_many_mut(children.iter());
+        while let Some((segment, mut sprite)) = iter.fetch_next() {
+            sprite.color = if segment.0 < filled {
+                FILLED.into()
+            } else {
+                EMPTY
+            };
+        }
+
+        let Some(phases) = phases else {
+            continue;
+        };
+
+        let mut iter = phase_names.iter_many_mut(children.iter());
+        while let Some(mut text) = iter.fetch_next() {
+            text.0 = phases.phase().name().unwrap_or_default().into();
+        }
+
+        let mut iter = timers.iter_many_mut(children.iter());
+        while let Some(mut text) = iter.fetch_next() {
+            text.0 = phases
+                .time_left()
+                .map(|secs| format!("{:.0}", secs.ceil()))
+                .unwrap_or_default();
+        }
+    }
+}
+
+fn despawn_hud(
+    trigger: Trigger<OnRemove, Boss>,
+    mut commands: Commands,
+    huds: Query<(Entity, &BossHud)>,
+) {
+    for (entity, hud) in huds.iter() {
+        if hud.boss == trigger.target() {
+            // already gone if the game is restarting
+            commands.entity(entity).try_despawn();
+        }
+    }
+}
@@ -1,11 +1,37 @@
+use crate::effects::hit::HitFeedback;
 use bevy::prelude::*;
 
+pub mod capture;
 pub mod gradius;
+pub mod hud;
+pub mod phases;
 
 pub struct BossPlugin;
 
 impl Plugin for BossPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(gradius::GradiusPlugin);
+        app.add_event::<BossDefeated>()
+            .add_plugins((
+                phases::BossPhasesPlugin,
+                capture::CapturePlugin,
+                hud::BossHudPlugin,
+                gradius::GradiusPlugin,
+            ))
+            .add_observer(boss_feedback);
     }
 }
+
+/// Marks an enemy as a boss. Bosses with [`Health`](crate::health::Health) get a health bar.
+#[derive(Default, Component)]
+pub struct Boss;
+
+/// Replaces the [`HitFeedback::ENEMY`] every boss gets as an enemy.
+fn boss_feedback(trigger: Trigger<OnAdd, Boss>, mut commands: Commands) {
+    commands.entity(trigger.target()).insert(HitFeedback::BOSS);
+}
+
+/// Written when a boss is destroyed, clearing the stage.
+#[derive(Event)]
+pub struct BossDefeated {
+    pub position: Vec2,
+}
@@ -0,0 +1,392 @@
+//! Bosses that work through an ordered list of [`BossPhase`]s.
+//!
+//! Every phase spawns its emitters under a [`PhaseEmitters`] child of the boss, which is
+//! despawned when the phase ends. The boss then plays the phase's [`PhaseTransition`] before the
+//! next one starts.
+//!
+//! A phase with a [time limit](BossPhase::time_limit) is skipped once it runs out. The last phase
+//! can not be skipped, so the boss fights on until it dies. A phase's
+//! [capture bonus](BossPhase::capture_bonus) is only paid if it did not time out and the player
+//! neither died nor bombed, see [`capture`](super::capture).
+use crate::{
+    GameState,
+    bullet::{Bullet, BulletSource, PlayerBullet, light::LightBullets},
+    effects::{Explosion, SpawnExplosion},
+    health::{Dead, Health, Invincible},
+    points::PointEvent,
+};
+use bevy::prelude::*;
+use bevy_optix::shake::TraumaCommands;
+use std::sync::Arc;
+
+/// Points for each bullet cleared between phases.
+const CLEAR_POINTS: usize = 2;
+
+pub struct BossPhasesPlugin;
+
+impl Plugin for BossPhasesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PhaseEnded>()
+            .add_systems(Update, update_phases.run_if(in_state(GameState::Game)));
+    }
+}
+
+/// When a [`BossPhase`] is over.
+#[derive(Debug, Clone, Copy)]
+pub enum PhaseEnd {
+    /// Health dropped to this fraction of the maximum.
+    Health(f32),
+    /// Seconds spent in the phase.
+    After(f32),
+}
+
+/// Played between two phases.
+#[derive(Debug, Clone, Copy)]
+pub struct PhaseTransition {
+    /// Seconds the boss can not be damaged for.
+    pub invulnerable: f32,
+    /// Clear enemy bullets, scoring for each one.
+    pub clear_bullets: bool,
+    pub trauma: f32,
+}
+
+impl Default for PhaseTransition {
+    fn default() -> Self {
+        Self {
+            invulnerable: 1.5,
+            clear_bullets: true,
+            trauma: 0.3,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct BossPhase {
+    end: PhaseEnd,
+    name: Option<&'static str>,
+    time_limit: Option<f32>,
+    capture_bonus: usize,
+    emitters: Arc<dyn Fn(&mut EntityCommands) + Send + Sync>,
+    transition: PhaseTransition,
+}
+
+impl BossPhase {
+    pub fn new(end: PhaseEnd) -> Self {
+        Self {
+            end,
+            name: None,
+            time_limit: None,
+            capture_bonus: 0,
+            emitters: Arc::new(|_| {}),
+            transition: PhaseTransition::default(),
+        }
+    }
+
+    /// Ends once health drops to `fraction` of the maximum.
+    pub fn until_health(fraction: f32) -> Self {
+        Self::new(PhaseEnd::Health(fraction))
+    }
+
+    pub fn timed(secs: f32) -> Self {
+        Self::new(PhaseEnd::After(secs))
+    }
+
+    /// Shown while the phase is active, and used to tell phases apart in the capture history.
+    pub fn named(mut self, name: &'static str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Skip the phase if it lasts longer than `secs`.
+    ///
+    /// The last phase only loses its capture bonus, it goes on until the boss dies.
+    pub fn time_limit(mut self, secs: f32) -> Self {
+        self.time_limit = Some(secs);
+        self
+    }
+
+    /// Paid if the phase is cleared without the player dying or bombing.
+    pub fn capture_bonus(mut self, points: usize) -> Self {
+        self.capture_bonus = points;
+        self
+    }
+
+    /// Spawn the phase's emitters as children of its [`PhaseEmitters`] entity.
+    pub fn emitters(
+        mut self,
+        emitters: impl Fn(&mut EntityCommands) + Send + Sync + 'static,
+    ) -> Self {
+        self.emitters = Arc::new(emitters);
+        self
+    }
+
+    /// Played when this phase ends.
+    pub fn transition(mut self, transition: PhaseTransition) -> Self {
+        self.transition = transition;
+        self
+    }
+
+    pub fn end(&self) -> PhaseEnd {
+        self.end
+    }
+
+    pub fn name(&self) -> Option<&'static str> {
+        self.name
+    }
+}
+
+/// The phases of a boss, in order.
+///
+/// The last phase lasts until the boss dies, whatever its [`PhaseEnd`] and time limit.
+#[derive(Component)]
+pub struct BossPhases {
+    phases: Vec<BossPhase>,
+    current: usize,
+    elapsed: f32,
+    invulnerable: f32,
+    emitters: Option<Entity>,
+    /// The player died or bombed during the current phase.
+    capture_failed: bool,
+    /// The last phase timed out and has already written its [`PhaseEnded`].
+    overtime: bool,
+    /// The last phase has ended with the boss's death.
+    finished: bool,
+}
+
+impl BossPhases {
+    /// # Panics
+    ///
+    /// Panics if there are no phases.
+    pub fn new(phases: impl IntoIterator<Item = BossPhase>) -> Self {
+        let phases = phases.into_iter().collect::<Vec<_>>();
+        assert!(!phases.is_empty(), "a boss needs at least one phase");
+        Self {
+            phases,
+            current: 0,
+            elapsed: 0.,
+            invulnerable: 0.,
+            emitters: None,
+            capture_failed: false,
+            overtime: false,
+            finished: false,
+        }
+    }
+
+    /// Index of the current phase.
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    pub fn phase(&self) -> &BossPhase {
+        &self.phases[self.current]
+    }
+
+    pub fn len(&self) -> usize {
+        self.phases.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.phases.is_empty()
+    }
+
+    /// Seconds spent in the current phase.
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    /// Seconds left before the current phase times out.
+    pub fn time_left(&self) -> Option<f32> {
+        self.phase()
+            .time_limit
+            .map(|limit| (limit - self.elapsed).max(0.))
+    }
+
+    /// Whether the current phase's capture bonus can still be earned.
+    pub fn capturable(&self) -> bool {
+        self.phase().capture_bonus > 0 && !self.capture_failed
+    }
+
+    pub(super) fn fail_capture(&mut self) {
+        self.capture_failed = true;
+    }
+
+    /// Fractions of health where a phase ends, for marking up a health bar.
+    pub fn markers(&self) -> impl Iterator<Item = f32> + '_ {
+        self.phases[..self.phases.len() - 1]
+            .iter()
+            .filter_map(|phase| match phase.end {
+                PhaseEnd::Health(fraction) => Some(fraction),
+                PhaseEnd::After(_) => None,
+            })
+    }
+
+    fn is_last(&self) -> bool {
+        self.current + 1 >= self.phases.len()
+    }
+
+    fn outcome(&self, health: &Health) -> Option<PhaseOutcome> {
+        if self.invulnerable > 0. || self.overtime {
+            return None;
+        }
+
+        let cleared = match self.phase().end {
+            PhaseEnd::Health(fraction) => health.current() <= health.max() * fraction,
+            PhaseEnd::After(secs) => self.elapsed >= secs,
+        };
+        if cleared && !self.is_last() {
+            Some(PhaseOutcome::Cleared)
+        } else if self.time_left() == Some(0.) {
+            Some(PhaseOutcome::TimedOut)
+        } else {
+            None
+        }
+    }
+
+    fn end_event(&self, boss: &str, outcome: PhaseOutcome, position: Vec2) -> PhaseEnded {
+        let phase = self.phase();
+        let name = match phase.name {
+            Some(name) => name.to_string(),
+            None => format!("phase {}", self.current + 1),
+        };
+        PhaseEnded {
+            key: format!("{boss}/{name}"),
+            outcome,
+            capture_bonus: phase.capture_bonus,
+            captured: outcome == PhaseOutcome::Cleared && self.capturable(),
+            position,
+        }
+    }
+
+    fn spawn_emitters(&mut self, commands: &mut Commands, boss: Entity) {
+        let mut root = commands.spawn((PhaseEmitters, ChildOf(boss)));
+        (self.phase().emitters)(&mut root);
+        self.emitters = Some(root.id());
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhaseOutcome {
+    /// Reached its [`PhaseEnd`], or the boss died.
+    Cleared,
+    /// Ran out of time and was skipped.
+    TimedOut,
+}
+
+/// Written when a boss phase ends.
+#[derive(Debug, Clone, Event)]
+pub struct PhaseEnded {
+    /// The boss's [`Name`] and the phase's name, unique across the game.
+    pub key: String,
+    pub outcome: PhaseOutcome,
+    pub capture_bonus: usize,
+    /// The bonus was earned.
+    pub captured: bool,
+    pub position: Vec2,
+}
+
+/// Parent of the current phase's emitters.
+#[derive(Component)]
+#[require(Transform, Visibility)]
+pub struct PhaseEmitters;
+
+fn update_phases(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut bosses: Query<(
+        Entity,
+        &mut BossPhases,
+        &mut Health,
+        &Transform,
+        Option<&Name>,
+        Has<Dead>,
+    )>,
+    bullets: Query<(Entity, &Transform), (With<Bullet>, Without<PlayerBullet>)>,
+    mut light_bullets: ResMut<LightBullets>,
+    mut points: EventWriter<PointEvent>,
+    mut explosions: EventWriter<SpawnExplosion>,
+    mut ended: EventWriter<PhaseEnded>,
+) {
+    for (entity, mut phases, mut health, transform, name, dead) in bosses.iter_mut() {
+        let boss = name.map(Name::as_str).unwrap_or("boss");
+        let position = transform.translation.xy();
+
+        if phases.finished {
+            continue;
+        }
+        if dead {
+            phases.finished = true;
+            if !phases.overtime {
+                ended.write(phases.end_event(boss, PhaseOutcome::Cleared, position));
+            }
+            continue;
+        }
+
+        if phases.emitters.is_none() {
+            phases.spawn_emitters(&mut commands, entity);
+            continue;
+        }
+
+        phases.elapsed += time.delta_secs();
+        if phases.invulnerable > 0. {
+            phases.invulnerable -= time.delta_secs();
+            if phases.invulnerable <= 0. {
+                commands.entity(entity).remove::<Invincible>();
+            }
+        }
+
+        let Some(outcome) = phases.outcome(&health) else {
+            continue;
+        };
+        ended.write(phases.end_event(boss, outcome, position));
+
+        if outcome == PhaseOutcome::TimedOut {
+            // running out the clock must not kill the boss, the player still has to
+            if phases.is_last() {
+                phases.overtime = true;
+                continue;
+            }
+            // skip ahead to where the phase would have ended
+            if let PhaseEnd::Health(fraction) = phases.phase().end {
+                let remaining = health.current() - health.max() * fraction;
+                if remaining > 0. {
+                    health.damage(remaining);
+                }
+            }
+        }
+
+        let transition = phases.phase().transition;
+        if let Some(emitters) = phases.emitters {
+            commands.entity(emitters).despawn();
+        }
+        phases.current += 1;
+        phases.elapsed = 0.;
+        phases.capture_failed = false;
+        phases.spawn_emitters(&mut commands, entity);
+
+        if transition.invulnerable > 0. {
+            phases.invulnerable = transition.invulnerable;
+            commands.entity(entity).insert(Invincible);
+        }
+
+        if transition.clear_bullets {
+            let cleared = bullets
+                .iter()
+                .map(|(entity, transform)| {
+                    commands.entity(entity).despawn();
+                    transform.translation.xy()
+                })
+                .chain(light_bullets.remove_source(BulletSource::Enemy));
+            for position in cleared {
+                points.write(PointEvent::new(CLEAR_POINTS, position));
+                explosions.write(SpawnExplosion {
+                    position,
+                    explosion: Explosion::Small,
+                });
+            }
+        }
+
+        if transition.trauma > 0. {
+            commands.add_trauma(transition.trauma);
+        }
+    }
+}
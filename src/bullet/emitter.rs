@@ -2,15 +2,17 @@ use super::{
     BasicBullet, BlueOrb, Bullet, BulletCollisionEvent, BulletSource, BulletSprite, BulletTimer,
     ColorMod, Lifetime, MaxLifetime, Missile, Polarity,
     homing::{Heading, Homing, TurnSpeed},
+    light::{LightBullet, LightBullets},
     player,
 };
 use crate::{
     Avian, DespawnRestart, HEIGHT, Layer,
     bullet::PlayerBullet,
     enemy::{Enemy, arcs::ArcsEmitter, minethrower::MineEmitter},
-    health::{Damage, DamageEvent, Health, HealthSet},
+    health::{Damage, DamageEvent, DamageKind, Health, HealthSet},
     particles::{self, ParticleAppExt, ParticleBundle, ParticleEmitter, ParticleState},
     player::Player,
+    rng::{GameRng, RngStream},
     sprites::{self, CellSize},
 };
 use avian2d::prelude::*;
@@ -23,7 +25,7 @@ use bevy::{
     time::Stopwatch,
 };
 use bevy_seedling::prelude::*;
-use rand::seq::IteratorRandom;
+use rand::{Rng, seq::IteratorRandom};
 use std::{f32::consts::PI, marker::PhantomData, time::Duration};
 use strum::IntoEnumIterator;
 
@@ -137,15 +139,17 @@ fn play_samples(
     mut commands: Commands,
     server: Res<AssetServer>,
     mut reader: EventReader<EmitterSample>,
+    mut rng: ResMut<GameRng>,
 ) {
+    let rng = rng.stream(RngStream::Audio);
     for event in reader.read() {
         match event.0 {
             EmitterBullet::Bullet => {
                 commands.spawn((
                     SamplePlayer::new(server.load("audio/sfx/bullet.wav")),
-                    PitchRange(BULLET_PITCH_RANGE),
                     PlaybackSettings {
                         volume: Volume::Decibels(-12.0),
+                        speed: rng.random_range(BULLET_PITCH_RANGE),
                         ..PlaybackSettings::ONCE
                     },
                     sample_effects![BandPassNode::new(1000.0, 4.0)],
@@ -214,11 +218,20 @@ pub struct ShotLimit(pub usize);
 #[require(Pulses)]
 pub struct PulseLimit(pub usize);
 
+/// Shots an emitter has taken since this was inserted.
+///
+/// A shot is one firing of the emitter, however many bullets it spawns.
 #[derive(Default, Component)]
-struct Shots(usize);
+pub struct Shots(pub(super) usize);
+
+impl Shots {
+    pub fn get(&self) -> usize {
+        self.0
+    }
+}
 
 #[derive(Default, Component)]
-struct Pulses(usize);
+pub(super) struct Pulses(pub(super) usize);
 
 fn limit(
     mut shots: Query<(&mut EmitterState, &Shots, &ShotLimit)>,
@@ -556,7 +569,7 @@ pub struct BulletCommands<'a, 'c> {
 }
 
 impl<'a, 'c> BulletCommands<'a, 'c> {
-    fn new(
+    pub(crate) fn new(
         commands: &'a mut Commands<'c, 'c>,
         mods: BulletModifiers,
         target: Target,
@@ -594,6 +607,22 @@ impl<'a, 'c> BulletCommands<'a, 'c> {
         bullet
     }
 
+    /// Queue a [`LightBullet`] instead of spawning an entity.
+    pub fn spawn_light(&mut self, angle_offset: f32, position: Vec2) {
+        let mut bullet = LightBullet::enemy(
+            position,
+            Vec2::from_angle(angle_offset)
+                .rotate(self.target.as_vec2())
+                .normalize_or_zero()
+                * self.speed
+                * self.mods.speed,
+        );
+        bullet.damage *= self.mods.damage;
+        self.commands.queue(move |world: &mut World| {
+            world.resource_mut::<LightBullets>().push(bullet);
+        });
+    }
+
     pub fn spawn_naked(&mut self, bundle: impl Bundle) -> EntityCommands<'_> {
         let mut bullet = self.commands.spawn(bundle);
         bullet.insert(Damage::new(1. * self.mods.damage));
@@ -911,12 +940,18 @@ impl BackgroundGattlingEmitter {
         let mods = world.get::<BulletModifiers>(ctx.entity).unwrap();
         let duration = mods.rate.duration(PLAYER_BULLET_RATE);
 
-        let mut rng = rand::rng();
+        let color = ColorMod::iter()
+            .choose(
+                world
+                    .resource_mut::<GameRng>()
+                    .stream(RngStream::Background),
+            )
+            .unwrap();
         world.commands().entity(ctx.entity).insert((
             BulletTimer {
                 timer: Timer::new(duration, TimerMode::Repeating),
             },
-            ColorMod::iter().choose(&mut rng).unwrap(),
+            color,
         ));
     }
 }
@@ -1311,17 +1346,16 @@ impl LaserEmitter {
 
                 if let Ok((entity, target_transform, player)) = targets.get(hit_data.entity) {
                     if (child.scale.x * 8.0 - hit_data.distance).abs() <= 16.0 {
-                        if emitter.layer == Layer::Enemy {
-                            damage_writer.write(DamageEvent {
-                                entity,
-                                damage: 15.0 * mods.damage * delta.as_secs_f32(),
-                            });
+                        let damage = if emitter.layer == Layer::Enemy {
+                            15.0 * mods.damage * delta.as_secs_f32()
                         } else {
-                            damage_writer.write(DamageEvent {
-                                entity,
-                                damage: 1. * mods.damage,
-                            });
-                        }
+                            1. * mods.damage
+                        };
+                        damage_writer.write(
+                            DamageEvent::new(entity, damage)
+                                .kind(DamageKind::Energy)
+                                .source(child_of.parent()),
+                        );
                     }
 
                     if let Some(mut timer) = timer {
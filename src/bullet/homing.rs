@@ -1,6 +1,7 @@
 use crate::Avian;
 use crate::enemy::Enemy;
 use crate::player::Player;
+use crate::rng::{GameRng, RngStream};
 use avian2d::prelude::*;
 use bevy::prelude::*;
 use rand::distr::{Distribution, weighted::WeightedIndex};
@@ -41,8 +42,9 @@ fn select_target<T: Component>(
     homing: Query<(Entity, &GlobalTransform), (With<Homing<T>>, Without<HomingTarget>)>,
     targets: Query<(Entity, &GlobalTransform), With<T>>,
     mut commands: Commands,
+    mut rng: ResMut<GameRng>,
 ) {
-    let mut rng = rand::rng();
+    let rng = rng.stream(RngStream::Homing);
 
     for (homing, homing_trans) in homing.iter() {
         let homing_trans = homing_trans.compute_transform();
@@ -72,7 +74,7 @@ fn select_target<T: Component>(
                 continue;
             };
 
-            let next_target = index.sample(&mut rng);
+            let next_target = index.sample(rng);
             distances[next_target].0
         };
 
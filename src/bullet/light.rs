@@ -0,0 +1,473 @@
+//! Lightweight bullets for dense patterns.
+//!
+//! Regular bullets are full entities with a rigid body, collider, and sprite. A [`LightBullet`]
+//! lives in the [`LightBullets`] store instead: it moves with plain kinematics, is tested against
+//! targets with a grid broadphase, and is drawn with a pool of recycled sprites.
+//!
+//! Light bullets write the same [`BulletCollisionEvent`], [`DamageEvent`] and [`GrazeEvent`] as
+//! regular bullets.
+use super::{BulletCollisionEvent, BulletSource, GRAZE_POINTS};
+use crate::{
+    Avian, GameState, Layer, assets,
+    health::{DamageEvent, Health, HealthSet},
+    hitbox::{GrazeEvent, Hitbox},
+    player::Player,
+    points::PointEvent,
+    sprites::{self, CellSize},
+};
+use avian2d::prelude::*;
+use bevy::{platform::collections::HashMap, prelude::*};
+use std::time::{Duration, Instant};
+
+const BROADPHASE_CELL: f32 = 16.;
+const MAX_RADIUS: f32 = 4.;
+const DESPAWN_MARGIN: f32 = 16.;
+
+pub struct LightBulletPlugin;
+
+impl Plugin for LightBulletPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LightBullets>()
+            .add_systems(
+                Avian,
+                (
+                    advance_light_bullets,
+                    graze_light_bullets,
+                    collide_light_bullets,
+                )
+                    .chain()
+                    .after(PhysicsSet::Sync)
+                    .before(HealthSet),
+            )
+            .add_systems(PostUpdate, sync_light_sprites)
+            .add_systems(OnEnter(GameState::Restart), clear_light_bullets);
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct LightBullet {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    /// Clamped to 4 units so that the broadphase only has to check a single cell.
+    pub radius: f32,
+    pub damage: f32,
+    pub source: BulletSource,
+    /// Cell in the colored projectile sheet, see [`super::BulletSprite::from_cell`].
+    pub cell: UVec2,
+    pub lifetime: f32,
+}
+
+impl LightBullet {
+    pub fn enemy(position: Vec2, velocity: Vec2) -> Self {
+        Self {
+            position,
+            velocity,
+            radius: 1.,
+            damage: 1.,
+            source: BulletSource::Enemy,
+            cell: UVec2::new(1, 2),
+            lifetime: 10.,
+        }
+    }
+}
+
+/// Struct of arrays store for every [`LightBullet`].
+///
+/// Dead bullets are swap removed, so the allocations are reused by the next bullets.
+#[derive(Default, Resource)]
+pub struct LightBullets {
+    position: Vec<Vec2>,
+    velocity: Vec<Vec2>,
+    radius: Vec<f32>,
+    damage: Vec<f32>,
+    source: Vec<BulletSource>,
+    cell: Vec<UVec2>,
+    lifetime: Vec<f32>,
+    grazed: Vec<bool>,
+}
+
+impl LightBullets {
+    pub fn push(&mut self, bullet: LightBullet) {
+        self.position.push(bullet.position);
+        self.velocity.push(bullet.velocity);
+        self.radius.push(bullet.radius.min(MAX_RADIUS));
+        self.damage.push(bullet.damage);
+        self.source.push(bullet.source);
+        self.cell.push(bullet.cell);
+        self.lifetime.push(bullet.lifetime);
+        self.grazed.push(false);
+    }
+
+    pub fn len(&self) -> usize {
+        self.position.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.position.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.position.clear();
+        self.velocity.clear();
+        self.radius.clear();
+        self.damage.clear();
+        self.source.clear();
+        self.cell.clear();
+        self.lifetime.clear();
+        self.grazed.clear();
+    }
+
+    /// Remove every bullet fired by `source`, returning where they were.
+    pub fn remove_source(&mut self, source: BulletSource) -> Vec<Vec2> {
+        self.remove_where(|bullets, i| bullets.source[i] == source)
+    }
+
+    /// Remove every bullet fired by `source` within `radius` of `center`, returning where they were.
+    pub fn remove_within(&mut self, source: BulletSource, center: Vec2, radius: f32) -> Vec<Vec2> {
+        self.remove_where(|bullets, i| {
+            bullets.source[i] == source && bullets.position[i].distance(center) <= radius
+        })
+    }
+
+    fn remove_where(&mut self, condition: impl Fn(&Self, usize) -> bool) -> Vec<Vec2> {
+        let mut removed = Vec::new();
+        for i in (0..self.len()).rev() {
+            if condition(self, i) {
+                removed.push(self.position[i]);
+                self.swap_remove(i);
+            }
+        }
+        removed
+    }
+
+    fn swap_remove(&mut self, index: usize) {
+        self.position.swap_remove(index);
+        self.velocity.swap_remove(index);
+        self.radius.swap_remove(index);
+        self.damage.swap_remove(index);
+        self.source.swap_remove(index);
+        self.cell.swap_remove(index);
+        self.lifetime.swap_remove(index);
+        self.grazed.swap_remove(index);
+    }
+
+    /// Move every bullet, then remove the ones that expired or left the screen.
+    pub fn advance(&mut self, delta: f32) {
+        for (position, velocity) in self.position.iter_mut().zip(self.velocity.iter()) {
+            *position += *velocity * delta;
+        }
+        for lifetime in self.lifetime.iter_mut() {
+            *lifetime -= delta;
+        }
+
+        let bounds = Vec2::new(crate::WIDTH, crate::HEIGHT) / 2. + DESPAWN_MARGIN;
+        for i in (0..self.len()).rev() {
+            let position = self.position[i];
+            if self.lifetime[i] <= 0. || position.x.abs() > bounds.x || position.y.abs() > bounds.y
+            {
+                self.swap_remove(i);
+            }
+        }
+    }
+}
+
+fn advance_light_bullets(mut bullets: ResMut<LightBullets>, time: Res<Time<Physics>>) {
+    bullets.advance(time.delta_secs());
+}
+
+/// Same rules as entity bullets: every enemy bullet can be grazed once.
+fn graze_light_bullets(
+    mut bullets: ResMut<LightBullets>,
+    player: Option<Single<(&Transform, &Hitbox), With<Player>>>,
+    mut writer: EventWriter<PointEvent>,
+    mut grazes: EventWriter<GrazeEvent>,
+) {
+    let Some(player) = player else {
+        return;
+    };
+    let (transform, hitbox) = player.into_inner();
+    let pp = transform.translation.xy();
+
+    let bullets = &mut *bullets;
+    for i in 0..bullets.position.len() {
+        let position = bullets.position[i];
+        if bullets.grazed[i]
+            || bullets.source[i] != BulletSource::Enemy
+            || position.distance(pp) >= hitbox.graze_radius
+        {
+            continue;
+        }
+
+        bullets.grazed[i] = true;
+        writer.write(PointEvent::new(GRAZE_POINTS, position));
+        grazes.write(GrazeEvent { position });
+    }
+}
+
+fn clear_light_bullets(mut bullets: ResMut<LightBullets>) {
+    bullets.clear();
+}
+
+struct BroadphaseTarget {
+    entity: Entity,
+    min: Vec2,
+    max: Vec2,
+    is_player: bool,
+    hit_by: BulletSource,
+}
+
+/// Uniform grid over the targets' AABBs. Every target is inserted into each cell it overlaps,
+/// grown by [`MAX_RADIUS`], so a bullet only has to look at the cell it is in.
+#[derive(Default)]
+struct Broadphase {
+    targets: Vec<BroadphaseTarget>,
+    cells: HashMap<IVec2, Vec<usize>>,
+}
+
+impl Broadphase {
+    fn cell(position: Vec2) -> IVec2 {
+        (position / BROADPHASE_CELL).floor().as_ivec2()
+    }
+
+    fn clear(&mut self) {
+        self.targets.clear();
+        // keep the cell allocations around
+        for indices in self.cells.values_mut() {
+            indices.clear();
+        }
+    }
+
+    fn insert(&mut self, target: BroadphaseTarget) {
+        let index = self.targets.len();
+        let min = Self::cell(target.min - MAX_RADIUS);
+        let max = Self::cell(target.max + MAX_RADIUS);
+        for x in min.x..=max.x {
+            for y in min.y..=max.y {
+                self.cells.entry(IVec2::new(x, y)).or_default().push(index);
+            }
+        }
+        self.targets.push(target);
+    }
+
+    fn query(&self, position: Vec2) -> impl Iterator<Item = &BroadphaseTarget> {
+        self.cells
+            .get(&Self::cell(position))
+            .into_iter()
+            .flatten()
+            .map(|i| &self.targets[*i])
+    }
+}
+
+/// Mirrors the layer rules of regular bullets: enemy bullets hit the player, player bullets hit
+/// enemies and destructable enemy bullets.
+fn hit_by(layers: &CollisionLayers, is_bullet: bool) -> Option<BulletSource> {
+    if !layers.filters.has_all(Layer::Bullet) {
+        return None;
+    }
+
+    if is_bullet {
+        layers
+            .filters
+            .has_all(Layer::Player)
+            .then_some(BulletSource::Player)
+    } else if layers.memberships.has_all(Layer::Player) {
+        Some(BulletSource::Enemy)
+    } else if layers.memberships.has_all(Layer::Enemy) {
+        Some(BulletSource::Player)
+    } else {
+        None
+    }
+}
+
+/// Like entity bullets, skips disabled colliders and sensors that are not [`Destructable`].
+///
+/// [`Destructable`]: super::Destructable
+fn collide_light_bullets(
+    mut bullets: ResMut<LightBullets>,
+    targets: Query<
+        (
+            Entity,
+            &ColliderAabb,
+            &CollisionLayers,
+            Option<&Player>,
+            Option<&super::Bullet>,
+        ),
+        (
+            With<Health>,
+            Without<ColliderDisabled>,
+            Or<(Without<Sensor>, With<super::Destructable>)>,
+        ),
+    >,
+    mut broadphase: Local<Broadphase>,
+    mut writer: EventWriter<BulletCollisionEvent>,
+    mut damage_writer: EventWriter<DamageEvent>,
+) {
+    if bullets.is_empty() {
+        return;
+    }
+
+    broadphase.clear();
+    for (entity, aabb, layers, player, bullet) in targets.iter() {
+        if let Some(hit_by) = hit_by(layers, bullet.is_some()) {
+            broadphase.insert(BroadphaseTarget {
+                entity,
+                min: aabb.min,
+                max: aabb.max,
+                is_player: player.is_some(),
+                hit_by,
+            });
+        }
+    }
+
+    for i in (0..bullets.len()).rev() {
+        let position = bullets.position[i];
+        let radius = bullets.radius[i];
+        let source = bullets.source[i];
+
+        let Some(target) = broadphase.query(position).find(|target| {
+            target.hit_by == source
+                && position
+                    .clamp(target.min, target.max)
+                    .distance_squared(position)
+                    <= radius * radius
+        }) else {
+            continue;
+        };
+
+        // light bullets are not entities, so their damage has no source
+        damage_writer.write(DamageEvent::new(target.entity, bullets.damage[i]));
+        writer.write(BulletCollisionEvent::new(
+            Transform::from_translation(position.extend(0.)),
+            source,
+            target.is_player,
+        ));
+        bullets.swap_remove(i);
+    }
+}
+
+/// A recycled sprite that draws one [`LightBullet`].
+#[derive(Component)]
+struct LightSprite(UVec2);
+
+fn sync_light_sprites(
+    mut commands: Commands,
+    bullets: Res<LightBullets>,
+    mut pool: Query<(
+        &mut LightSprite,
+        &mut Sprite,
+        &mut Transform,
+        &mut Visibility,
+    )>,
+    server: Res<AssetServer>,
+) {
+    let mut pool = pool.iter_mut();
+    for (position, cell) in bullets.position.iter().zip(bullets.cell.iter()) {
+        let translation = position.extend(0.);
+        match pool.next() {
+            Some((mut light, mut sprite, mut transform, mut visibility)) => {
+                if light.0 != *cell {
+                    light.0 = *cell;
+                    *sprite = sprites::sprite_rect(
+                        &server,
+                        assets::PROJECTILES_COLORED_PATH,
+                        CellSize::Eight,
+                        *cell,
+                    );
+                }
+                transform.translation = translation;
+                *visibility = Visibility::Inherited;
+            }
+            None => {
+                commands.spawn((
+                    LightSprite(*cell),
+                    sprites::sprite_rect(
+                        &server,
+                        assets::PROJECTILES_COLORED_PATH,
+                        CellSize::Eight,
+                        *cell,
+                    ),
+                    Transform::from_translation(translation),
+                ));
+            }
+        }
+    }
+
+    for (_, _, _, mut visibility) in pool {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+/// Headless benchmark, run with `cargo run --release -- --bench-bullets <n>`.
+///
+/// Spawns `n` light bullets in a ring around a handful of targets and reports the cost of the
+/// simulation and collision systems per tick.
+pub fn bench(bullets: usize) {
+    const TICKS: u32 = 600;
+    const DELTA: f32 = 1. / 64.;
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_event::<BulletCollisionEvent>()
+        .add_event::<DamageEvent>()
+        .init_resource::<LightBullets>()
+        .add_systems(
+            Update,
+            (
+                |mut bullets: ResMut<LightBullets>| bullets.advance(DELTA),
+                collide_light_bullets,
+                respawn_bench_bullets(bullets),
+            )
+                .chain(),
+        );
+
+    for i in 0..16 {
+        let position = Vec2::new((i % 4) as f32 * 32. - 48., (i / 4) as f32 * 48. - 72.);
+        app.world_mut().spawn((
+            Health::full(f32::MAX),
+            ColliderAabb::new(position, Vec2::splat(4.)),
+            CollisionLayers::new([Layer::Enemy], [Layer::Bullet, Layer::Player]),
+        ));
+    }
+    // stand-in for the player, `Player` itself pulls in the whole player plugin
+    app.world_mut().spawn((
+        Health::full(f32::MAX),
+        ColliderAabb::new(Vec2::ZERO, Vec2::ONE),
+        CollisionLayers::new([Layer::Player], [Layer::Bullet]),
+    ));
+
+    // warm up the allocations
+    app.update();
+
+    let mut total = Duration::ZERO;
+    let mut worst = Duration::ZERO;
+    for _ in 0..TICKS {
+        let start = Instant::now();
+        app.update();
+        let elapsed = start.elapsed();
+        total += elapsed;
+        worst = worst.max(elapsed);
+    }
+
+    println!(
+        "{bullets} light bullets: {:?} per tick on average, {:?} worst, over {TICKS} ticks",
+        total / TICKS,
+        worst
+    );
+}
+
+fn respawn_bench_bullets(count: usize) -> impl FnMut(ResMut<LightBullets>) {
+    let mut tick = 0usize;
+    move |mut bullets: ResMut<LightBullets>| {
+        tick += 1;
+        while bullets.len() < count {
+            let i = bullets.len() + tick;
+            let angle = i as f32 * 0.618 * std::f32::consts::TAU;
+            let direction = Vec2::from_angle(angle);
+            let mut bullet = LightBullet::enemy(direction * 8., direction * 40.);
+            if i % 2 == 0 {
+                bullet.source = BulletSource::Player;
+            }
+            bullets.push(bullet);
+        }
+    }
+}
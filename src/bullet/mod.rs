@@ -6,9 +6,11 @@ use crate::{
     auto_collider::ImageCollider,
     bounds::WallDespawn,
     effects::{AlwaysBlast, Blasters, Explosion, SpawnExplosion},
-    health::{Damage, DamageEvent, Dead, Health},
+    health::{Damage, DamageEvent, DamageKind, Dead, Health},
+    hitbox::{GrazeEvent, Hitbox},
     player::Player,
     points::PointEvent,
+    rng::{GameRng, RngStream},
     sprites::{self, CellSize},
     tween::OnEnd,
 };
@@ -31,9 +33,12 @@ use strum_macros::EnumIter;
 
 pub mod emitter;
 pub mod homing;
+pub mod light;
+pub mod motion;
+pub mod pattern;
 pub mod player;
+pub mod script;
 
-const GRAZE_DIST: f32 = 15.;
 const GRAZE_POINTS: usize = 5;
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
@@ -47,39 +52,46 @@ pub struct BulletPlugin;
 
 impl Plugin for BulletPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((emitter::EmitterPlugin, homing::HomingPlugin))
-            .add_event::<BulletCollisionEvent>()
-            .add_systems(
-                PreUpdate,
-                (grazing, handle_bullet_collision, despawn_dead_bullets)
-                    .chain()
-                    .in_set(BulletSystems::Collision),
-            )
-            .add_systems(
-                Update,
-                (
-                    manage_lifetime.in_set(BulletSystems::Lifetime),
-                    bullet_collision_effects,
-                ),
-            )
-            .add_systems(
-                PostUpdate,
-                init_bullet_sprite
-                    .in_set(BulletSystems::Sprite)
-                    .after(BulletSystems::Collision),
-            )
-            .register_layout(
-                "orb.png",
-                TextureAtlasLayout::from_grid(UVec2::splat(8), 3, 1, None, None),
-            )
-            .register_layout(
-                "orb1.png",
-                TextureAtlasLayout::from_grid(UVec2::splat(8), 3, 1, None, None),
-            )
-            .register_layout(
-                "bomb.png",
-                TextureAtlasLayout::from_grid(UVec2::splat(8), 8, 1, None, None),
-            );
+        app.add_plugins((
+            emitter::EmitterPlugin,
+            homing::HomingPlugin,
+            light::LightBulletPlugin,
+            motion::MotionPlugin,
+            pattern::PatternPlugin,
+            script::ScriptPlugin,
+        ))
+        .add_event::<BulletCollisionEvent>()
+        .add_systems(
+            PreUpdate,
+            (grazing, handle_bullet_collision, despawn_dead_bullets)
+                .chain()
+                .in_set(BulletSystems::Collision),
+        )
+        .add_systems(
+            Update,
+            (
+                manage_lifetime.in_set(BulletSystems::Lifetime),
+                bullet_collision_effects,
+            ),
+        )
+        .add_systems(
+            PostUpdate,
+            init_bullet_sprite
+                .in_set(BulletSystems::Sprite)
+                .after(BulletSystems::Collision),
+        )
+        .register_layout(
+            "orb.png",
+            TextureAtlasLayout::from_grid(UVec2::splat(8), 3, 1, None, None),
+        )
+        .register_layout(
+            "orb1.png",
+            TextureAtlasLayout::from_grid(UVec2::splat(8), 3, 1, None, None),
+        )
+        .register_layout(
+            "bomb.png",
+            TextureAtlasLayout::from_grid(UVec2::splat(8), 8, 1, None, None),
+        );
     }
 }
 
@@ -184,7 +196,7 @@ impl Default for Lifetime {
 }
 
 impl Lifetime {
-    fn new(secs: f32) -> Self {
+    pub fn new(secs: f32) -> Self {
         Self(Timer::new(Duration::from_secs_f32(secs), TimerMode::Once))
     }
 }
@@ -204,6 +216,7 @@ fn manage_lifetime(mut q: Query<(Entity, &mut Lifetime)>, time: Res<Time>, mut c
 #[derive(Clone, Copy, Component, Default)]
 #[require(
     Polarity,
+    DamageKind,
     Sensor,
     RigidBody::Kinematic,
     WallDespawn,
@@ -276,6 +289,7 @@ pub struct Arrow;
     Bullet,
     Destructable,
     Health::full(MISSILE_HEALTH),
+    DamageKind::Explosive,
     Collider::rectangle(2., 2.),
     BulletSprite::from_cell(5, 5)
 )]
@@ -286,6 +300,7 @@ pub struct Missile;
     Bullet,
     Collider::rectangle(2., 2.),
     BulletSprite::from_cell(5, 2),
+    DamageKind::Explosive,
     Blasters(const { &[Vec3::new(0., -5., -1.)] }),
     AlwaysBlast,
 )]
@@ -296,6 +311,7 @@ pub struct Rocket;
     Bullet,
     Destructable,
     Health::full(MINE_HEALTH),
+    DamageKind::Explosive,
     Collider::circle(1.5),
     AnimationSprite::repeating("bomb.png", 0.1, 0..8),
 )]
@@ -330,24 +346,33 @@ struct Grazed;
 fn grazing(
     mut commands: Commands,
     mut writer: EventWriter<PointEvent>,
-    player: Single<&Transform, With<Player>>,
+    mut grazes: EventWriter<GrazeEvent>,
+    player: Single<(&Transform, &Hitbox), With<Player>>,
     bullets: Query<(Entity, &Transform), (With<Bullet>, Without<PlayerBullet>, Without<Grazed>)>,
 ) {
-    let pp = player.translation.xy();
+    let (transform, hitbox) = player.into_inner();
+    let pp = transform.translation.xy();
     for (entity, transform) in bullets.iter() {
         let position = transform.translation.xy();
-        if position.distance(pp) < GRAZE_DIST {
+        if position.distance(pp) < hitbox.graze_radius {
             commands.entity(entity).insert(Grazed);
-            writer.write(PointEvent {
-                points: GRAZE_POINTS,
-                position,
-            });
+            writer.write(PointEvent::new(GRAZE_POINTS, position));
+            grazes.write(GrazeEvent { position });
         }
     }
 }
 
 fn handle_bullet_collision(
-    bullets: Query<(Entity, &Damage, &GlobalTransform, &CollisionLayers), With<Bullet>>,
+    bullets: Query<
+        (
+            Entity,
+            &Damage,
+            &DamageKind,
+            &GlobalTransform,
+            &CollisionLayers,
+        ),
+        With<Bullet>,
+    >,
     destructable: Query<
         (
             Entity,
@@ -366,11 +391,11 @@ fn handle_bullet_collision(
     for (entity, colliding_entities, destructable_layers, player, destructable_bullet) in
         destructable.iter()
     {
-        for (bullet, damage, transform, layers) in colliding_entities
+        for (bullet, damage, kind, transform, layers) in colliding_entities
             .iter()
             .copied()
             .flat_map(|entity| bullets.get(entity))
-            .filter(|(_, _, _, layers)| {
+            .filter(|(_, _, _, _, layers)| {
                 if destructable_bullet.is_some() {
                     destructable_layers.filters.has_all(Layer::Player)
                         && layers.filters.has_all(Layer::Enemy)
@@ -380,10 +405,11 @@ fn handle_bullet_collision(
             })
         {
             if despawned.insert(bullet) {
-                damage_writer.write(DamageEvent {
-                    damage: damage.damage(),
-                    entity,
-                });
+                damage_writer.write(
+                    DamageEvent::new(entity, damage.damage())
+                        .kind(*kind)
+                        .source(bullet),
+                );
 
                 let source = if layers.filters.has_all(Layer::Player) {
                     BulletSource::Enemy
@@ -409,12 +435,13 @@ fn despawn_dead_bullets(
     mines: Query<(Entity, &Transform), (With<Dead>, With<Mine>)>,
     mut writer: EventWriter<PointEvent>,
     mut explosions: EventWriter<SpawnExplosion>,
+    mut rng: ResMut<GameRng>,
 ) {
     for entity in bullets.iter() {
         commands.entity(entity).despawn();
     }
 
-    let mut rng = rand::rng();
+    let rng = rng.stream(RngStream::Bullets);
     for (entity, transform) in mines.iter() {
         commands.entity(entity).despawn();
         let dirs = [
@@ -481,10 +508,7 @@ fn despawn_dead_bullets(
             explosion: Explosion::Small,
         });
 
-        writer.write(PointEvent {
-            points: 10,
-            position: transform.translation.xy(),
-        });
+        writer.write(PointEvent::new(10, transform.translation.xy()));
     }
 }
 
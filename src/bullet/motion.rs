@@ -0,0 +1,283 @@
+use super::{Lifetime, emitter::Target};
+use crate::{Avian, player::Player};
+use avian2d::prelude::*;
+use bevy::{
+    math::curve::{Curve, EaseFunction},
+    prelude::*,
+};
+use std::time::Duration;
+
+pub struct MotionPlugin;
+
+impl Plugin for MotionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Avian,
+            (accelerate, apply_speed_curve, curve_velocity, redirect)
+                .chain()
+                .before(PhysicsSet::StepSimulation),
+        );
+    }
+}
+
+/// Speeds up (or slows down, if negative) a bullet along its current direction.
+///
+/// The speed is clamped to `min..=max`, so a negative rate can bring a bullet to a stop. A stopped
+/// bullet keeps its heading, and speeds up along it again if the rate turns positive.
+#[derive(Debug, Clone, Copy, Component)]
+pub struct Acceleration {
+    pub rate: f32,
+    pub min: f32,
+    pub max: f32,
+    heading: Vec2,
+}
+
+impl Acceleration {
+    pub fn new(rate: f32) -> Self {
+        Self {
+            rate,
+            min: 0.,
+            max: f32::MAX,
+            heading: Vec2::NEG_Y,
+        }
+    }
+
+    pub fn clamped(mut self, min: f32, max: f32) -> Self {
+        self.min = min;
+        self.max = max;
+        self
+    }
+
+    /// Direction for bullets that spawn at rest.
+    pub fn heading(mut self, heading: Vec2) -> Self {
+        self.heading = heading.normalize_or(Vec2::NEG_Y);
+        self
+    }
+
+    fn apply(&mut self, velocity: Vec2, delta: f32) -> Vec2 {
+        if let Some(direction) = velocity.try_normalize() {
+            self.heading = direction;
+        }
+
+        let speed = (velocity.length() + self.rate * delta).clamp(self.min, self.max);
+        self.heading * speed
+    }
+}
+
+fn accelerate(
+    mut bullets: Query<(&mut Acceleration, &mut LinearVelocity)>,
+    time: Res<Time<Physics>>,
+) {
+    let delta = time.delta_secs();
+    for (mut acceleration, mut velocity) in bullets.iter_mut() {
+        velocity.0 = acceleration.apply(velocity.0, delta);
+    }
+}
+
+/// Scales a bullet's speed by a curve over its [`Lifetime`].
+///
+/// The speed the bullet spawned with is treated as `1.0`.
+#[derive(Debug, Clone, Copy, Component)]
+#[require(Lifetime)]
+pub struct SpeedCurve {
+    pub start: f32,
+    pub end: f32,
+    pub ease: EaseFunction,
+    base: Option<f32>,
+    direction: Vec2,
+}
+
+impl SpeedCurve {
+    pub fn new(start: f32, end: f32, ease: EaseFunction) -> Self {
+        Self {
+            start,
+            end,
+            ease,
+            base: None,
+            direction: Vec2::NEG_Y,
+        }
+    }
+
+    /// The velocity at `fraction` of the bullet's lifetime.
+    fn apply(&mut self, velocity: Vec2, fraction: f32) -> Vec2 {
+        let base = *self.base.get_or_insert(velocity.length());
+        // a bullet that stops still needs to remember where it was going
+        let direction = velocity.try_normalize().unwrap_or(self.direction);
+        self.direction = direction;
+
+        let t = self.ease.sample_clamped(fraction);
+        direction * base * self.start.lerp(self.end, t)
+    }
+}
+
+fn apply_speed_curve(mut bullets: Query<(&mut SpeedCurve, &Lifetime, &mut LinearVelocity)>) {
+    for (mut curve, lifetime, mut velocity) in bullets.iter_mut() {
+        velocity.0 = curve.apply(velocity.0, lifetime.0.fraction());
+    }
+}
+
+/// Rotates a bullet's velocity by `n` radians per second, making it travel in an arc.
+#[derive(Debug, Clone, Copy, Component)]
+pub struct Curving(pub f32);
+
+fn curve_velocity(
+    mut bullets: Query<(&Curving, &mut LinearVelocity, Option<&mut Rotation>)>,
+    time: Res<Time<Physics>>,
+) {
+    let delta = time.delta_secs();
+    for (curving, mut velocity, rotation) in bullets.iter_mut() {
+        let angle = curving.0 * delta;
+        velocity.0 = Vec2::from_angle(angle).rotate(velocity.0);
+        if let Some(mut rotation) = rotation {
+            *rotation = Rotation::radians(rotation.as_radians() + angle);
+        }
+    }
+}
+
+/// Changes a bullet's direction after a delay, e.g. a bullet that stops and then re-aims at the
+/// player.
+///
+/// With `brake` set, the bullet slows to a stop over the delay. [`Target::Player`] is resolved
+/// when the delay finishes.
+#[derive(Clone, Copy, Component)]
+pub struct Redirect {
+    pub delay: Duration,
+    pub brake: bool,
+    pub target: Target,
+    pub speed: f32,
+    elapsed: Duration,
+    initial: Option<Vec2>,
+}
+
+impl Redirect {
+    pub fn new(delay: f32, target: Target, speed: f32) -> Self {
+        Self {
+            delay: Duration::from_secs_f32(delay),
+            brake: false,
+            target,
+            speed,
+            elapsed: Duration::ZERO,
+            initial: None,
+        }
+    }
+
+    pub fn stop_and_aim(delay: f32, speed: f32) -> Self {
+        Self {
+            brake: true,
+            ..Self::new(delay, Target::player(), speed)
+        }
+    }
+
+    fn fraction(&self) -> f32 {
+        if self.delay.is_zero() {
+            1.
+        } else {
+            (self.elapsed.as_secs_f32() / self.delay.as_secs_f32()).min(1.)
+        }
+    }
+}
+
+fn redirect(
+    mut commands: Commands,
+    mut bullets: Query<(
+        Entity,
+        &mut Redirect,
+        &GlobalTransform,
+        &mut LinearVelocity,
+        Option<&mut Rotation>,
+    )>,
+    player: Option<Single<&Transform, With<Player>>>,
+    time: Res<Time<Physics>>,
+) {
+    for (entity, mut redirect, gt, mut velocity, rotation) in bullets.iter_mut() {
+        let initial = *redirect.initial.get_or_insert(velocity.0);
+        redirect.elapsed += time.delta();
+
+        if redirect.fraction() < 1. {
+            if redirect.brake {
+                velocity.0 = initial * (1. - redirect.fraction());
+            }
+            continue;
+        }
+
+        let direction = match (redirect.target, &player) {
+            (Target::Player { .. }, Some(player)) => {
+                (player.translation.xy() - gt.translation().xy()).normalize_or_zero()
+            }
+            (target, _) => target.as_vec2(),
+        };
+
+        velocity.0 = direction * redirect.speed;
+        if let (Some(mut rotation), Some(previous)) = (rotation, initial.try_normalize()) {
+            *rotation = Rotation::radians(rotation.as_radians() + previous.angle_to(direction));
+        }
+
+        commands.entity(entity).remove::<Redirect>();
+    }
+}
+
+/// Attach motion to bullets spawned through [`BulletCommands`](super::emitter::BulletCommands).
+pub trait BulletMotion {
+    fn accelerate(&mut self, acceleration: Acceleration) -> &mut Self;
+
+    fn speed_curve(&mut self, start: f32, end: f32, ease: EaseFunction) -> &mut Self;
+
+    fn curving(&mut self, radians_per_sec: f32) -> &mut Self;
+
+    fn redirect(&mut self, redirect: Redirect) -> &mut Self;
+}
+
+impl BulletMotion for EntityCommands<'_> {
+    fn accelerate(&mut self, acceleration: Acceleration) -> &mut Self {
+        self.insert(acceleration)
+    }
+
+    fn speed_curve(&mut self, start: f32, end: f32, ease: EaseFunction) -> &mut Self {
+        self.insert(SpeedCurve::new(start, end, ease))
+    }
+
+    fn curving(&mut self, radians_per_sec: f32) -> &mut Self {
+        self.insert(Curving(radians_per_sec))
+    }
+
+    fn redirect(&mut self, redirect: Redirect) -> &mut Self {
+        self.insert(redirect)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn acceleration_restarts_a_stopped_bullet() {
+        let mut acceleration = Acceleration::new(-10.);
+        let velocity = acceleration.apply(Vec2::X * 5., 1.);
+        assert_eq!(velocity, Vec2::ZERO);
+
+        acceleration.rate = 10.;
+        assert_eq!(acceleration.apply(velocity, 0.5), Vec2::X * 5.);
+    }
+
+    #[test]
+    fn acceleration_is_clamped() {
+        let mut acceleration = Acceleration::new(100.).clamped(0., 20.);
+        assert_eq!(acceleration.apply(Vec2::Y * 10., 1.), Vec2::Y * 20.);
+
+        let mut acceleration = Acceleration::new(5.).heading(Vec2::X * 3.);
+        assert_eq!(acceleration.apply(Vec2::ZERO, 1.), Vec2::X * 5.);
+    }
+
+    #[test]
+    fn speed_curve_scales_the_spawn_speed() {
+        let mut curve = SpeedCurve::new(1., 0., EaseFunction::Linear);
+        assert_eq!(curve.apply(Vec2::X * 10., 0.), Vec2::X * 10.);
+        assert_eq!(curve.apply(Vec2::X * 10., 0.5), Vec2::X * 5.);
+
+        // stopping halfway does not forget the base speed or the direction
+        let velocity = curve.apply(Vec2::X * 5., 1.);
+        assert_eq!(velocity, Vec2::ZERO);
+        curve.end = 2.;
+        assert_eq!(curve.apply(velocity, 1.), Vec2::X * 20.);
+    }
+}
@@ -0,0 +1,441 @@
+use super::{
+    Arrow, BasicBullet, BlueOrb, BulletTimer, Mine, Missile, RedOrb,
+    emitter::{
+        BulletCommands, BulletModifiers, BulletSpeed, Emitter, EmitterBullet, EmitterDelay,
+        EmitterSample, EmitterState, EmitterSystems, EmitterTimer, PulseTime, PulseTimer, Pulses,
+        Shots, Target,
+    },
+};
+use crate::assets::RonAssetAppExt;
+use avian2d::prelude::*;
+use bevy::{
+    ecs::{component::HookContext, world::DeferredWorld},
+    prelude::*,
+};
+use serde::Deserialize;
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, TAU};
+
+pub struct PatternPlugin;
+
+impl Plugin for PatternPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_ron_asset::<BulletPattern>(&["pattern.ron"])
+            .add_systems(
+                PreUpdate,
+                (reload_patterns, PatternEmitter::shoot_bullets)
+                    .chain()
+                    .in_set(EmitterSystems::Update),
+            );
+    }
+}
+
+/// A data-driven bullet pattern, loaded from `assets/patterns/*.pattern.ron`.
+///
+/// Every shot spawns each of the [`PatternShape`]s, aimed with the pattern's [`PatternAim`].
+#[derive(Debug, Clone, Asset, TypePath, Deserialize)]
+pub struct BulletPattern {
+    pub timing: PatternTiming,
+    #[serde(default)]
+    pub aim: PatternAim,
+    pub bullet: PatternBullet,
+    pub speed: f32,
+    pub shapes: Vec<PatternShape>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub enum PatternTiming {
+    /// Shoot every `n` seconds.
+    Interval(f32),
+    /// Shoot `pulses` times, `shot` seconds apart, then wait for `wait` seconds.
+    ///
+    /// See [`PulseTimer`].
+    Pulse { wait: f32, shot: f32, pulses: usize },
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+pub enum PatternAim {
+    #[default]
+    Player,
+    /// Track the player while waiting, but lock the aim for the duration of a pulse.
+    PlayerLocked,
+    Direction(Vec2),
+    /// Keep the emitter's own [`Target`], for aims that are set in code.
+    Emitter,
+}
+
+impl PatternAim {
+    pub fn target(self) -> Option<Target> {
+        match self {
+            Self::Player | Self::PlayerLocked => Some(Target::player()),
+            Self::Direction(dir) => Some(Target::dir(dir)),
+            Self::Emitter => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub enum PatternBullet {
+    Basic,
+    Arrow,
+    RedOrb,
+    BlueOrb,
+    Missile,
+    Mine,
+}
+
+impl PatternBullet {
+    pub(super) fn insert(self, entity: &mut EntityCommands, direction: Vec2) {
+        match self {
+            Self::Basic => entity.insert(BasicBullet),
+            Self::Arrow => entity.insert(Arrow),
+            Self::RedOrb => entity.insert(RedOrb),
+            Self::BlueOrb => entity.insert(BlueOrb),
+            Self::Missile => entity.insert(Missile),
+            Self::Mine => entity.insert(Mine),
+        };
+
+        if let Some(offset) = self.rotation_offset() {
+            entity.insert(Rotation::radians(direction.to_angle() + offset));
+        }
+    }
+
+    // orbs and mines spin on their own
+    pub(super) fn rotation_offset(self) -> Option<f32> {
+        match self {
+            Self::Basic => Some(-FRAC_PI_2),
+            Self::Arrow | Self::Missile => Some(-FRAC_PI_2 + FRAC_PI_4),
+            Self::RedOrb | Self::BlueOrb | Self::Mine => None,
+        }
+    }
+
+    pub(super) fn sample(self) -> EmitterBullet {
+        match self {
+            Self::Basic => EmitterBullet::Bullet,
+            Self::Arrow => EmitterBullet::Arrow,
+            Self::RedOrb | Self::BlueOrb => EmitterBullet::Orb,
+            Self::Missile => EmitterBullet::Missile,
+            Self::Mine => EmitterBullet::Mine,
+        }
+    }
+}
+
+/// Angles are in radians, relative to the aim direction. Shapes with no `bullets` spawn nothing.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub enum PatternShape {
+    Single {
+        #[serde(default)]
+        angle: f32,
+    },
+    /// `bullets` evenly spaced around a full circle.
+    ///
+    /// Each pulse rotates the ring by `pulse_rotation`. If `alternate` is set, even pulses
+    /// rotate the other way.
+    Ring {
+        bullets: usize,
+        #[serde(default)]
+        offset: f32,
+        #[serde(default)]
+        pulse_rotation: f32,
+        #[serde(default)]
+        alternate: bool,
+    },
+    /// `bullets` evenly spaced across `spread`, centered on the aim direction.
+    Arc {
+        bullets: usize,
+        spread: f32,
+        #[serde(default)]
+        offset: f32,
+    },
+    /// A wall of `bullets` perpendicular to the aim direction, `gap` units apart.
+    ///
+    /// The outer bullets are pushed forward by `bowl`, or held back if it is negative.
+    Line {
+        bullets: usize,
+        gap: f32,
+        #[serde(default)]
+        bowl: f32,
+    },
+}
+
+impl PatternShape {
+    fn spawn(
+        &self,
+        commands: &mut BulletCommands,
+        bullet: PatternBullet,
+        transform: Transform,
+        target: Vec2,
+        pulse: usize,
+    ) {
+        match *self {
+            Self::Single { angle } => {
+                spawn_angled(commands, bullet, transform, target, angle);
+            }
+            Self::Ring {
+                bullets,
+                offset,
+                pulse_rotation,
+                alternate,
+            } => {
+                let mut rotation = pulse as f32 * pulse_rotation;
+                if alternate && pulse % 2 == 0 {
+                    rotation *= -1.;
+                }
+
+                for i in 0..bullets {
+                    let angle = (i as f32 / bullets as f32) * TAU + offset + rotation;
+                    spawn_angled(commands, bullet, transform, target, angle);
+                }
+            }
+            Self::Arc {
+                bullets,
+                spread,
+                offset,
+            } => {
+                match bullets {
+                    0 => return,
+                    1 => {
+                        spawn_angled(commands, bullet, transform, target, offset);
+                        return;
+                    }
+                    _ => {}
+                }
+
+                let step = spread / (bullets - 1) as f32;
+                for i in 0..bullets {
+                    let angle = -spread / 2. + i as f32 * step + offset;
+                    spawn_angled(commands, bullet, transform, target, angle);
+                }
+            }
+            Self::Line { bullets, gap, bowl } => {
+                let center_x = bullets.saturating_sub(1) as f32 * gap / 2.;
+
+                for i in 0..bullets {
+                    let x = i as f32 * gap - center_x;
+                    let y = if center_x == 0. {
+                        0.
+                    } else {
+                        bowl * (x / center_x).powi(2)
+                    };
+
+                    let mut transform = transform;
+                    transform.translation += (target.perp() * x + target * y).extend(0.);
+                    spawn_angled(commands, bullet, transform, target, 0.);
+                }
+            }
+        }
+    }
+}
+
+fn spawn_angled(
+    commands: &mut BulletCommands,
+    bullet: PatternBullet,
+    transform: Transform,
+    target: Vec2,
+    angle: f32,
+) {
+    let direction = Vec2::from_angle(angle).rotate(target);
+    let mut entity = commands.spawn_angled(angle, transform);
+    bullet.insert(&mut entity, direction);
+}
+
+#[derive(Component)]
+pub enum PatternTimer {
+    Interval(BulletTimer),
+    Pulse(PulseTimer),
+}
+
+impl PatternTimer {
+    pub fn new(timing: PatternTiming, mods: &BulletModifiers) -> Self {
+        match timing {
+            PatternTiming::Interval(secs)
+            | PatternTiming::Pulse {
+                wait: secs,
+                pulses: 0..=1,
+                ..
+            } => Self::Interval(BulletTimer::ready(mods.rate.duration(secs).as_secs_f32())),
+            PatternTiming::Pulse { wait, shot, pulses } => {
+                Self::Pulse(PulseTimer::ready(mods.rate, wait, shot, pulses))
+            }
+        }
+    }
+
+    pub fn is_waiting(&self) -> bool {
+        match self {
+            Self::Interval(_) => true,
+            Self::Pulse(timer) => timer.is_waiting(),
+        }
+    }
+
+    pub fn current_pulse(&self) -> usize {
+        match self {
+            Self::Interval(_) => 0,
+            Self::Pulse(timer) => timer.current_pulse(),
+        }
+    }
+
+    pub fn pulses(&self) -> usize {
+        match self {
+            Self::Interval(_) => 1,
+            Self::Pulse(timer) => timer.pulses(),
+        }
+    }
+}
+
+impl EmitterTimer for PatternTimer {
+    fn tick(&mut self, time: &Time) {
+        match self {
+            Self::Interval(timer) => EmitterTimer::tick(timer, time),
+            Self::Pulse(timer) => EmitterTimer::tick(timer, time),
+        }
+    }
+
+    fn shoot(&self) -> bool {
+        match self {
+            Self::Interval(timer) => timer.shoot(),
+            Self::Pulse(timer) => timer.shoot(),
+        }
+    }
+
+    fn finished_pulse(&self) -> bool {
+        match self {
+            Self::Interval(timer) => timer.finished_pulse(),
+            Self::Pulse(timer) => timer.finished_pulse(),
+        }
+    }
+}
+
+/// Plays a [`BulletPattern`] through the regular emitter path.
+///
+/// Respects [`EmitterState`], [`EmitterDelay`], [`BulletModifiers`], and shot/pulse limits. The
+/// pattern's speed replaces the emitter's [`BulletSpeed`], and its aim replaces the [`Target`]
+/// unless it is [`PatternAim::Emitter`].
+///
+/// The pattern at `path` is loaded when the emitter is inserted.
+#[derive(Clone, Component)]
+#[require(Transform, Emitter)]
+#[component(on_insert = Self::load)]
+pub struct PatternEmitter {
+    path: &'static str,
+    handle: Handle<BulletPattern>,
+}
+
+impl PatternEmitter {
+    pub fn new(path: &'static str) -> Self {
+        Self {
+            path,
+            handle: Handle::default(),
+        }
+    }
+
+    pub fn handle(&self) -> &Handle<BulletPattern> {
+        &self.handle
+    }
+
+    fn load(mut world: DeferredWorld, ctx: HookContext) {
+        let path = world.get::<Self>(ctx.entity).unwrap().path;
+        let handle = world.resource::<AssetServer>().load(path);
+        world.get_mut::<Self>(ctx.entity).unwrap().handle = handle;
+    }
+
+    fn shoot_bullets(
+        mut commands: Commands,
+        mut emitters: Query<
+            (
+                Entity,
+                &PatternEmitter,
+                &EmitterState,
+                Option<&mut PatternTimer>,
+                &BulletModifiers,
+                &BulletSpeed,
+                &mut Target,
+                Option<&ChildOf>,
+                &GlobalTransform,
+                Option<&mut Shots>,
+                Option<&mut Pulses>,
+            ),
+            Without<EmitterDelay>,
+        >,
+        patterns: Res<Assets<BulletPattern>>,
+        parents: Query<Option<&BulletModifiers>>,
+        time: Res<Time>,
+        mut writer: EventWriter<EmitterSample>,
+    ) {
+        for (entity, emitter, state, timer, mods, speed, mut target, child_of, gt, shots, pulses) in
+            emitters.iter_mut()
+        {
+            if !state.enabled {
+                continue;
+            }
+
+            let Some(pattern) = patterns.get(&emitter.handle) else {
+                continue;
+            };
+
+            let mods = if let Some(Ok(Some(parent_mods))) =
+                child_of.map(|child_of| parents.get(child_of.parent()))
+            {
+                parent_mods.join(mods)
+            } else {
+                *mods
+            };
+
+            let Some(mut timer) = timer else {
+                let mut entity = commands.entity(entity);
+                entity.insert((
+                    PatternTimer::new(pattern.timing, &mods),
+                    BulletSpeed::new(pattern.speed),
+                ));
+                if let Some(target) = pattern.aim.target() {
+                    entity.insert(target);
+                }
+                continue;
+            };
+
+            timer.tick(&time);
+            if matches!(pattern.aim, PatternAim::PlayerLocked) {
+                target.enable(timer.is_waiting());
+            }
+
+            if timer.finished_pulse() {
+                if let Some(mut pulses) = pulses {
+                    pulses.0 += 1;
+                }
+            }
+            if !timer.shoot() {
+                continue;
+            }
+
+            if let Some(mut shots) = shots {
+                shots.0 += 1;
+            }
+
+            let transform = gt.compute_transform();
+            let direction = target.as_vec2();
+            let pulse = timer.current_pulse();
+            let mut bullets = BulletCommands::new(&mut commands.reborrow(), mods, *target, speed.0);
+            for shape in pattern.shapes.iter() {
+                shape.spawn(&mut bullets, pattern.bullet, transform, direction, pulse);
+            }
+
+            writer.write(EmitterSample(pattern.bullet.sample()));
+        }
+    }
+}
+
+/// Restart emitters whose pattern changed on disk.
+fn reload_patterns(
+    mut commands: Commands,
+    mut reader: EventReader<AssetEvent<BulletPattern>>,
+    emitters: Query<(Entity, &PatternEmitter), With<PatternTimer>>,
+) {
+    for event in reader.read() {
+        let AssetEvent::Modified { id } = event else {
+            continue;
+        };
+
+        for (entity, _) in emitters.iter().filter(|(_, e)| e.handle.id() == *id) {
+            info!("reloading bullet pattern for {entity}");
+            commands.entity(entity).remove::<PatternTimer>();
+        }
+    }
+}
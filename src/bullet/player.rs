@@ -2,7 +2,8 @@ use super::{BasicBullet, Bullet, Missile, PlayerBullet, emitter::*};
 use crate::Layer;
 use crate::health::Damage;
 use crate::particles::{self, *};
-use crate::player::{PowerUps, ShotKind};
+use crate::player::{Power, ShotKind};
+use crate::ship::{Ship, ShotTable};
 use avian2d::prelude::*;
 use bevy::ecs::component::HookContext;
 use bevy::ecs::world::DeferredWorld;
@@ -52,9 +53,9 @@ impl PlayerGattlingEmitter {
             ),
             With<PlayerGattlingEmitter>,
         >,
-        parents: Query<Option<&BulletModifiers>, With<Children>>,
+        parents: Query<(Option<&BulletModifiers>, &Ship), With<Children>>,
         time: Res<Time>,
-        power: Option<Res<PowerUps>>,
+        power: Option<Res<Power>>,
         mut commands: Commands,
     ) {
         let Some(power) = power else {
@@ -71,7 +72,7 @@ impl PlayerGattlingEmitter {
                 continue;
             }
 
-            let Ok(parent_mods) = parents.get(child_of.parent()) else {
+            let Ok((parent_mods, ship)) = parents.get(child_of.parent()) else {
                 continue;
             };
             let mods = parent_mods.map(|m| m.join(mods)).unwrap_or(*mods);
@@ -79,134 +80,19 @@ impl PlayerGattlingEmitter {
             let mut new_transform = transform.compute_transform();
             new_transform.translation += Vec3::Y * 1.0;
 
-            spawn_normal_bullets(
+            spawn_shots(
                 &mut commands,
                 &mut timer,
                 ShotKind::Normal,
                 new_transform,
                 &mods,
+                &ship.0.normal,
                 power.get(),
             );
         }
     }
 }
 
-fn spawn_normal_bullets(
-    commands: &mut Commands,
-    timer: &mut PulseTimer,
-    kind: ShotKind,
-    transform: Transform,
-    mods: &BulletModifiers,
-    power: usize,
-) {
-    match power {
-        0 => {
-            spawn_bullet(
-                commands,
-                kind,
-                mods,
-                transform
-                    .with_translation(transform.translation.with_x(transform.translation.x - 2.5)),
-                Vec2::Y * PLAYER_BULLET_SPEED,
-            );
-            spawn_bullet(
-                commands,
-                kind,
-                mods,
-                transform
-                    .with_translation(transform.translation.with_x(transform.translation.x + 2.5)),
-                Vec2::Y * PLAYER_BULLET_SPEED,
-            );
-        }
-        1 => {
-            timer.wait.set_duration(Duration::from_secs_f32(0.2));
-            let angle = 0.1;
-            spawn_bullet(
-                commands,
-                kind,
-                mods,
-                transform
-                    .with_translation(transform.translation.with_x(transform.translation.x + 2.5))
-                    .with_rotation(Quat::from_rotation_z(-angle)),
-                (Vec2::Y + Vec2::new(angle, 0.)).normalize() * PLAYER_BULLET_SPEED,
-            );
-            spawn_bullet(
-                commands,
-                kind,
-                mods,
-                transform,
-                Vec2::Y * PLAYER_BULLET_SPEED,
-            );
-            spawn_bullet(
-                commands,
-                kind,
-                mods,
-                transform
-                    .with_translation(transform.translation.with_x(transform.translation.x - 2.5))
-                    .with_rotation(Quat::from_rotation_z(angle)),
-                (Vec2::Y - Vec2::new(angle, 0.)).normalize() * PLAYER_BULLET_SPEED,
-            );
-        }
-        2 => {
-            timer.wait.set_duration(Duration::from_secs_f32(0.1));
-            let angle = 0.1;
-            spawn_bullet(
-                commands,
-                kind,
-                mods,
-                transform
-                    .with_translation(transform.translation.with_x(transform.translation.x + 7.5))
-                    .with_rotation(Quat::from_rotation_z(-2. * angle)),
-                (Vec2::Y + Vec2::new(2. * angle, 0.)).normalize() * PLAYER_BULLET_SPEED,
-            );
-            spawn_bullet(
-                commands,
-                kind,
-                mods,
-                transform
-                    .with_translation(transform.translation.with_x(transform.translation.x + 5.))
-                    .with_rotation(Quat::from_rotation_z(-angle)),
-                (Vec2::Y + Vec2::new(angle, 0.)).normalize() * PLAYER_BULLET_SPEED,
-            );
-            spawn_bullet(
-                commands,
-                kind,
-                mods,
-                transform
-                    .with_translation(transform.translation.with_x(transform.translation.x - 2.5)),
-                Vec2::Y * PLAYER_BULLET_SPEED,
-            );
-            spawn_bullet(
-                commands,
-                kind,
-                mods,
-                transform
-                    .with_translation(transform.translation.with_x(transform.translation.x + 2.5)),
-                Vec2::Y * PLAYER_BULLET_SPEED,
-            );
-            spawn_bullet(
-                commands,
-                kind,
-                mods,
-                transform
-                    .with_translation(transform.translation.with_x(transform.translation.x - 5.))
-                    .with_rotation(Quat::from_rotation_z(angle)),
-                (Vec2::Y - Vec2::new(angle, 0.)).normalize() * PLAYER_BULLET_SPEED,
-            );
-            spawn_bullet(
-                commands,
-                kind,
-                mods,
-                transform
-                    .with_translation(transform.translation.with_x(transform.translation.x - 7.5))
-                    .with_rotation(Quat::from_rotation_z(2. * angle)),
-                (Vec2::Y - Vec2::new(2. * angle, 0.)).normalize() * PLAYER_BULLET_SPEED,
-            );
-        }
-        _ => error!("invalid power level: {}", power),
-    }
-}
-
 #[derive(Default, Component)]
 #[require(
     Transform,
@@ -249,9 +135,9 @@ impl PlayerFocusEmitter {
             ),
             With<PlayerFocusEmitter>,
         >,
-        parents: Query<Option<&BulletModifiers>, With<Children>>,
+        parents: Query<(Option<&BulletModifiers>, &Ship), With<Children>>,
         time: Res<Time>,
-        power: Option<Res<PowerUps>>,
+        power: Option<Res<Power>>,
         mut commands: Commands,
     ) {
         let Some(power) = power else {
@@ -268,7 +154,7 @@ impl PlayerFocusEmitter {
                 continue;
             }
 
-            let Ok(parent_mods) = parents.get(child_of.parent()) else {
+            let Ok((parent_mods, ship)) = parents.get(child_of.parent()) else {
                 continue;
             };
             let mods = parent_mods.map(|m| m.join(mods)).unwrap_or(*mods);
@@ -276,119 +162,48 @@ impl PlayerFocusEmitter {
             let mut new_transform = transform.compute_transform();
             new_transform.translation += Vec3::Y * 1.0;
 
-            spawn_focus_bullets(
+            spawn_shots(
                 &mut commands,
                 &mut timer,
                 ShotKind::Focus,
                 new_transform,
                 &mods,
+                &ship.0.focus,
                 power.get(),
             );
         }
     }
 }
 
-fn spawn_focus_bullets(
+/// Fire the shots a ship's [`ShotTable`] selects for `power`.
+fn spawn_shots(
     commands: &mut Commands,
     timer: &mut PulseTimer,
     kind: ShotKind,
     transform: Transform,
     mods: &BulletModifiers,
-    power: usize,
+    table: &ShotTable,
+    power: f32,
 ) {
-    match power {
-        0 => {
-            spawn_bullet(
-                commands,
-                kind,
-                mods,
-                transform
-                    .with_translation(transform.translation.with_x(transform.translation.x - 2.5)),
-                Vec2::Y * PLAYER_BULLET_SPEED,
-            );
-            spawn_bullet(
-                commands,
-                kind,
-                mods,
-                transform
-                    .with_translation(transform.translation.with_x(transform.translation.x + 2.5)),
-                Vec2::Y * PLAYER_BULLET_SPEED,
-            );
-        }
-        1 => {
-            timer.wait.set_duration(Duration::from_secs_f32(0.2));
-            spawn_bullet(
-                commands,
-                kind,
-                mods,
-                transform
-                    .with_translation(transform.translation.with_x(transform.translation.x + 2.5)),
-                Vec2::Y * PLAYER_BULLET_SPEED,
-            );
-            spawn_bullet(
-                commands,
-                kind,
-                mods,
-                transform,
-                Vec2::Y * PLAYER_BULLET_SPEED,
-            );
-            spawn_bullet(
-                commands,
-                kind,
-                mods,
-                transform
-                    .with_translation(transform.translation.with_x(transform.translation.x - 2.5)),
-                Vec2::Y * PLAYER_BULLET_SPEED,
-            );
-        }
-        2 => {
-            timer.wait.set_duration(Duration::from_secs_f32(0.1));
-            spawn_bullet(
-                commands,
-                kind,
-                mods,
-                transform.with_translation(transform.translation + Vec3::new(7.5, -3., 0.)),
-                Vec2::Y * PLAYER_BULLET_SPEED,
-            );
-            spawn_bullet(
-                commands,
-                kind,
-                mods,
-                transform.with_translation(transform.translation + Vec3::new(5., -2., 0.)),
-                Vec2::Y * PLAYER_BULLET_SPEED,
-            );
-            spawn_bullet(
-                commands,
-                kind,
-                mods,
-                transform
-                    .with_translation(transform.translation.with_x(transform.translation.x - 2.5)),
-                Vec2::Y * PLAYER_BULLET_SPEED,
-            );
-            spawn_bullet(
-                commands,
-                kind,
-                mods,
-                transform
-                    .with_translation(transform.translation.with_x(transform.translation.x + 2.5)),
-                Vec2::Y * PLAYER_BULLET_SPEED,
-            );
-            spawn_bullet(
-                commands,
-                kind,
-                mods,
-                transform.with_translation(transform.translation + Vec3::new(-5., -2., 0.)),
-                Vec2::Y * PLAYER_BULLET_SPEED,
-            );
-            spawn_bullet(
-                commands,
-                kind,
-                mods,
-                transform.with_translation(transform.translation + Vec3::new(-7.5, -3., 0.)),
-                Vec2::Y * PLAYER_BULLET_SPEED,
-            );
-        }
-        _ => error!("invalid power level: {}", power),
+    let Some(level) = table.level(power) else {
+        error!("no shots for power {power:.2}");
+        return;
+    };
+
+    if let Some(wait) = level.wait {
+        timer.wait.set_duration(Duration::from_secs_f32(wait));
+    }
+
+    for shot in level.shots.iter() {
+        spawn_bullet(
+            commands,
+            kind,
+            mods,
+            transform
+                .with_translation(transform.translation + shot.offset.extend(0.))
+                .with_rotation(Quat::from_rotation_z(-shot.angle)),
+            Vec2::from_angle(-shot.angle).rotate(Vec2::Y) * PLAYER_BULLET_SPEED,
+        );
     }
 }
 
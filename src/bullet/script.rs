@@ -0,0 +1,496 @@
+use super::{
+    emitter::{
+        BulletCommands, BulletModifiers, Emitter, EmitterDelay, EmitterSample, EmitterState,
+        EmitterSystems, Rate, Target,
+    },
+    pattern::PatternBullet,
+};
+use crate::{assets::RonAssetAppExt, player::Player};
+use avian2d::prelude::*;
+use bevy::{
+    ecs::{component::HookContext, world::DeferredWorld},
+    prelude::*,
+};
+use serde::Deserialize;
+use std::{
+    f32::consts::{PI, TAU},
+    sync::Arc,
+};
+
+pub struct ScriptPlugin;
+
+impl Plugin for ScriptPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_ron_asset::<BulletScript>(&["script.ron"])
+            .add_systems(
+                PreUpdate,
+                (reload_scripts, ScriptEmitter::init_runners, run_scripts)
+                    .chain()
+                    .in_set(EmitterSystems::Update),
+            );
+    }
+}
+
+/// A BulletML-style bullet script, loaded from `assets/patterns/*.script.ron`.
+///
+/// A [`ScriptEmitter`] runs `actions` top to bottom, then starts over. Bullets fired with their
+/// own `actions` run them once, which lets a bullet act as an emitter itself.
+#[derive(Debug, Clone, Asset, TypePath, Deserialize)]
+pub struct BulletScript {
+    /// Fired by [`Action::Fire`] when no bullet is given.
+    pub bullet: PatternBullet,
+    /// Fired by [`Action::Fire`] when no speed is given.
+    pub speed: f32,
+    pub actions: Arc<[Action]>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub enum Action {
+    Repeat {
+        times: usize,
+        actions: Arc<[Action]>,
+    },
+    /// Wait for `n` seconds. Scaled by the emitter's [`Rate::Factor`].
+    Wait(f32),
+    Fire {
+        #[serde(default)]
+        direction: Direction,
+        #[serde(default)]
+        speed: Option<Speed>,
+        #[serde(default)]
+        bullet: Option<PatternBullet>,
+        /// Run by the fired bullet.
+        #[serde(default)]
+        actions: Option<Arc<[Action]>>,
+    },
+    /// Change the bullet's speed over `duration` seconds.
+    ChangeSpeed {
+        speed: Speed,
+        duration: f32,
+    },
+    /// Turn the bullet over `duration` seconds, taking the shortest way around.
+    ChangeDirection {
+        direction: Direction,
+        duration: f32,
+    },
+    Vanish,
+}
+
+/// Angles are in radians, counter-clockwise.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub enum Direction {
+    /// Relative to the direction of the player, or the emitter's [`Target`].
+    Aim(f32),
+    /// Relative to straight down.
+    Absolute(f32),
+    /// Relative to the direction the bullet is travelling in.
+    Relative(f32),
+    /// Relative to the last fired bullet.
+    Sequence(f32),
+}
+
+impl Default for Direction {
+    fn default() -> Self {
+        Self::Aim(0.)
+    }
+}
+
+impl Direction {
+    fn resolve(self, aim: f32, heading: f32, last: Option<f32>) -> f32 {
+        match self {
+            Self::Aim(angle) => aim + angle,
+            Self::Absolute(angle) => Vec2::NEG_Y.to_angle() + angle,
+            Self::Relative(angle) => heading + angle,
+            Self::Sequence(angle) => last.unwrap_or(aim) + angle,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub enum Speed {
+    Absolute(f32),
+    /// Relative to the speed of the bullet.
+    Relative(f32),
+    /// Relative to the last fired bullet.
+    Sequence(f32),
+}
+
+impl Speed {
+    fn resolve(self, speed: f32, last: Option<f32>) -> f32 {
+        match self {
+            Self::Absolute(s) => s,
+            Self::Relative(s) => speed + s,
+            Self::Sequence(s) => last.unwrap_or(speed) + s,
+        }
+    }
+}
+
+/// Runs a [`BulletScript`].
+///
+/// Respects [`EmitterState`], [`EmitterDelay`], and [`BulletModifiers`]. Aims with the emitter's
+/// [`Target`], so insert [`Target::player`] to aim at the player.
+///
+/// The script at `path` is loaded when the emitter is inserted.
+#[derive(Clone, Component)]
+#[require(Transform, Emitter)]
+#[component(on_insert = Self::load)]
+pub struct ScriptEmitter {
+    path: &'static str,
+    handle: Handle<BulletScript>,
+}
+
+impl ScriptEmitter {
+    pub fn new(path: &'static str) -> Self {
+        Self {
+            path,
+            handle: Handle::default(),
+        }
+    }
+
+    pub fn handle(&self) -> &Handle<BulletScript> {
+        &self.handle
+    }
+
+    fn load(mut world: DeferredWorld, ctx: HookContext) {
+        let path = world.get::<Self>(ctx.entity).unwrap().path;
+        let handle = world.resource::<AssetServer>().load(path);
+        world.get_mut::<Self>(ctx.entity).unwrap().handle = handle;
+    }
+
+    fn init_runners(
+        mut commands: Commands,
+        emitters: Query<
+            (Entity, &ScriptEmitter, &BulletModifiers, Option<&ChildOf>),
+            Without<ScriptRunner>,
+        >,
+        scripts: Res<Assets<BulletScript>>,
+        parents: Query<Option<&BulletModifiers>>,
+    ) {
+        for (entity, emitter, mods, child_of) in emitters.iter() {
+            let Some(script) = scripts.get(&emitter.handle) else {
+                continue;
+            };
+
+            let mods = if let Some(Ok(Some(parent_mods))) =
+                child_of.map(|child_of| parents.get(child_of.parent()))
+            {
+                parent_mods.join(mods)
+            } else {
+                *mods
+            };
+
+            commands.entity(entity).insert(ScriptRunner {
+                looping: true,
+                ..ScriptRunner::new(
+                    script.actions.clone(),
+                    script.bullet,
+                    script.speed,
+                    mods,
+                    None,
+                )
+            });
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct ScriptRunner {
+    root: Arc<[Action]>,
+    stack: Vec<Frame>,
+    looping: bool,
+    wait: f32,
+    mods: BulletModifiers,
+
+    bullet: PatternBullet,
+    fire_speed: f32,
+    last_direction: Option<f32>,
+    last_speed: Option<f32>,
+
+    // Only bullets have a heading. Emitters use their aim.
+    heading: Option<f32>,
+    speed: f32,
+    speed_change: Option<Change>,
+    direction_change: Option<Change>,
+}
+
+struct Frame {
+    actions: Arc<[Action]>,
+    index: usize,
+    remaining: usize,
+}
+
+struct Change {
+    from: f32,
+    to: f32,
+    elapsed: f32,
+    duration: f32,
+}
+
+impl Change {
+    fn new(from: f32, to: f32, duration: f32) -> Self {
+        Self {
+            from,
+            to,
+            elapsed: 0.,
+            duration,
+        }
+    }
+
+    fn tick(&mut self, delta: f32) -> f32 {
+        self.elapsed += delta;
+        self.from.lerp(self.to, self.progress())
+    }
+
+    fn progress(&self) -> f32 {
+        if self.duration <= 0. {
+            1.
+        } else {
+            (self.elapsed / self.duration).min(1.)
+        }
+    }
+
+    fn finished(&self) -> bool {
+        self.progress() >= 1.
+    }
+}
+
+impl ScriptRunner {
+    fn new(
+        actions: Arc<[Action]>,
+        bullet: PatternBullet,
+        speed: f32,
+        mods: BulletModifiers,
+        heading: Option<f32>,
+    ) -> Self {
+        Self {
+            stack: vec![Frame {
+                actions: actions.clone(),
+                index: 0,
+                remaining: 1,
+            }],
+            root: actions,
+            looping: false,
+            wait: 0.,
+            mods,
+            bullet,
+            fire_speed: speed,
+            last_direction: None,
+            last_speed: None,
+            heading,
+            speed,
+            speed_change: None,
+            direction_change: None,
+        }
+    }
+
+    fn next_action(&mut self) -> Option<Action> {
+        loop {
+            let frame = self.stack.last_mut()?;
+            if let Some(action) = frame.actions.get(frame.index) {
+                frame.index += 1;
+                return Some(action.clone());
+            }
+
+            frame.remaining = frame.remaining.saturating_sub(1);
+            if frame.remaining > 0 {
+                frame.index = 0;
+            } else {
+                self.stack.pop();
+            }
+        }
+    }
+
+    fn restart(&mut self) {
+        self.stack.push(Frame {
+            actions: self.root.clone(),
+            index: 0,
+            remaining: 1,
+        });
+    }
+
+    fn wait_secs(&self, secs: f32) -> f32 {
+        match self.mods.rate {
+            Rate::Factor(factor) => secs / factor,
+            Rate::Secs(_) => secs,
+        }
+    }
+
+    /// Returns true if the heading or speed changed.
+    fn tick_changes(&mut self, delta: f32) -> bool {
+        let mut changed = false;
+
+        if let Some(change) = &mut self.speed_change {
+            self.speed = change.tick(delta);
+            changed = true;
+            if change.finished() {
+                self.speed_change = None;
+            }
+        }
+
+        if let Some(change) = &mut self.direction_change {
+            self.heading = Some(change.tick(delta));
+            changed = true;
+            if change.finished() {
+                self.direction_change = None;
+            }
+        }
+
+        changed
+    }
+}
+
+fn shortest_angle(from: f32, to: f32) -> f32 {
+    from + (to - from + PI).rem_euclid(TAU) - PI
+}
+
+fn run_scripts(
+    mut commands: Commands,
+    mut runners: Query<
+        (
+            Entity,
+            &mut ScriptRunner,
+            &GlobalTransform,
+            Option<&Target>,
+            Option<&EmitterState>,
+            Option<&mut LinearVelocity>,
+            Option<&mut Rotation>,
+        ),
+        Without<EmitterDelay>,
+    >,
+    player: Option<Single<&Transform, With<Player>>>,
+    time: Res<Time>,
+    mut writer: EventWriter<EmitterSample>,
+) {
+    let delta = time.delta_secs();
+
+    for (entity, mut runner, gt, target, state, velocity, rotation) in runners.iter_mut() {
+        if state.is_some_and(|state| !state.enabled) {
+            continue;
+        }
+
+        if runner.tick_changes(delta) {
+            if let (Some(heading), Some(mut velocity)) = (runner.heading, velocity) {
+                velocity.0 = Vec2::from_angle(heading) * runner.speed * runner.mods.speed;
+                if let (Some(offset), Some(mut rotation)) =
+                    (runner.bullet.rotation_offset(), rotation)
+                {
+                    *rotation = Rotation::radians(heading + offset);
+                }
+            }
+        }
+
+        let position = gt.translation().xy();
+        let aim = match (target, &player) {
+            (Some(target), _) => target.as_vec2(),
+            (None, Some(player)) => (player.translation.xy() - position).normalize_or_zero(),
+            (None, None) => Vec2::NEG_Y,
+        }
+        .to_angle();
+        let heading = runner.heading.unwrap_or(aim);
+
+        let mut fired = None;
+        let mut restarted = false;
+        runner.wait -= delta;
+        while runner.wait <= 0. {
+            let Some(action) = runner.next_action() else {
+                // a script without any waits would otherwise loop forever
+                if runner.looping && !restarted {
+                    runner.restart();
+                    restarted = true;
+                    continue;
+                }
+                runner.wait = 0.;
+                break;
+            };
+
+            match action {
+                Action::Repeat { times, actions } => {
+                    if times > 0 {
+                        runner.stack.push(Frame {
+                            actions,
+                            index: 0,
+                            remaining: times,
+                        });
+                    }
+                }
+                Action::Wait(secs) => {
+                    runner.wait += runner.wait_secs(secs);
+                }
+                Action::Fire {
+                    direction,
+                    speed,
+                    bullet,
+                    actions,
+                } => {
+                    let angle = direction.resolve(aim, heading, runner.last_direction);
+                    let speed = speed.map_or(runner.fire_speed, |speed| {
+                        speed.resolve(runner.speed, runner.last_speed)
+                    });
+                    let bullet = bullet.unwrap_or(runner.bullet);
+                    runner.last_direction = Some(angle);
+                    runner.last_speed = Some(speed);
+
+                    let direction = Vec2::from_angle(angle);
+                    let mut bullets = BulletCommands::new(
+                        &mut commands.reborrow(),
+                        runner.mods,
+                        Target::dir(direction),
+                        Vec2::splat(speed),
+                    );
+                    let mut entity = bullets.spawn(Transform::from_translation(gt.translation()));
+                    bullet.insert(&mut entity, direction);
+                    if let Some(actions) = actions {
+                        entity.insert(ScriptRunner::new(
+                            actions,
+                            bullet,
+                            speed,
+                            runner.mods,
+                            Some(angle),
+                        ));
+                    }
+
+                    fired = Some(bullet);
+                }
+                Action::ChangeSpeed { speed, duration } => {
+                    let to = speed.resolve(runner.speed, runner.last_speed);
+                    runner.speed_change = Some(Change::new(runner.speed, to, duration));
+                }
+                Action::ChangeDirection {
+                    direction,
+                    duration,
+                } => {
+                    let to = direction.resolve(aim, heading, runner.last_direction);
+                    runner.direction_change =
+                        Some(Change::new(heading, shortest_angle(heading, to), duration));
+                }
+                Action::Vanish => {
+                    commands.entity(entity).try_despawn();
+                    break;
+                }
+            }
+        }
+
+        // sub-bullets are too noisy
+        if let (Some(bullet), Some(_)) = (fired, state) {
+            writer.write(EmitterSample(bullet.sample()));
+        }
+    }
+}
+
+/// Restart emitters whose script changed on disk.
+fn reload_scripts(
+    mut commands: Commands,
+    mut reader: EventReader<AssetEvent<BulletScript>>,
+    emitters: Query<(Entity, &ScriptEmitter), With<ScriptRunner>>,
+) {
+    for event in reader.read() {
+        let AssetEvent::Modified { id } = event else {
+            continue;
+        };
+
+        for (entity, _) in emitters.iter().filter(|(_, e)| e.handle.id() == *id) {
+            info!("reloading bullet script for {entity}");
+            commands.entity(entity).remove::<ScriptRunner>();
+        }
+    }
+}
@@ -0,0 +1,365 @@
+//! A [`Campaign`] plays its stages in order without ever leaving [`GameState::Game`].
+//!
+//! Every stage opens with an intro card once its file has loaded, and closes with a tally once its
+//! boss is defeated. The player entity and the score, bomb, and power resources are left alone
+//! between stages, so they carry over into the next one.
+use crate::{
+    Avian, DespawnRestart, GameState,
+    background::Backdrop,
+    boss::BossDefeated,
+    end,
+    enemy::stage::Stage,
+    health::{DamageDealt, HealthSet},
+    hitbox::GrazeMeter,
+    input::Interact,
+    player::Player,
+    points::PointEvent,
+    stats::Stats,
+};
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+use bevy_enhanced_input::prelude::*;
+use bevy_optix::pixel_perfect::HIGH_RES_LAYER;
+
+const STAGES: &[&str] = &["stages/stage1.stage.ron", "stages/stage2.stage.ron"];
+
+/// Seconds the intro card is shown before the stage starts.
+const INTRO_SECS: f32 = 2.5;
+/// Seconds between the boss's death and the tally, enough to collect what it dropped.
+const OUTRO_SECS: f32 = 3.;
+
+/// Awarded for clearing a stage, multiplied by the stage's number.
+const CLEAR_BONUS: usize = 1000;
+/// Awarded for clearing a stage without being hit.
+const NO_MISS_BONUS: usize = 2000;
+/// Awarded per bullet grazed during the stage.
+const GRAZE_BONUS: usize = 10;
+
+pub struct CampaignPlugin;
+
+impl Plugin for CampaignPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_sub_state::<StagePhase>()
+            .init_resource::<StageTally>()
+            .add_systems(Startup, load_campaign)
+            .add_systems(OnEnter(GameState::Restart), restart)
+            .add_systems(OnEnter(StagePhase::Intro), (spawn_intro_card, set_backdrop))
+            .add_systems(OnEnter(StagePhase::Playing), start_tally)
+            .add_systems(OnEnter(StagePhase::Outro), start_outro)
+            .add_systems(OnExit(StagePhase::Intro), despawn_cards)
+            .add_systems(OnExit(StagePhase::Outro), despawn_cards)
+            .add_systems(
+                Update,
+                (
+                    wait_for_stage.run_if(in_state(StagePhase::Loading)),
+                    end_intro.run_if(in_state(StagePhase::Intro)),
+                    clear_stage.run_if(in_state(StagePhase::Playing)),
+                    show_tally.run_if(in_state(StagePhase::Outro)),
+                ),
+            )
+            .add_systems(
+                Avian,
+                count_misses
+                    .after(HealthSet)
+                    .run_if(in_state(StagePhase::Playing)),
+            )
+            .add_observer(next_stage);
+    }
+}
+
+/// Where the current stage is at. Only exists during [`GameState::Game`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, SubStates)]
+#[source(GameState = GameState::Game)]
+pub enum StagePhase {
+    /// Waiting for the stage's file, so the intro has something to show.
+    #[default]
+    Loading,
+    /// The stage's intro card, before any waves.
+    Intro,
+    Playing,
+    /// The boss is defeated. Ends with the stage-clear tally.
+    Outro,
+}
+
+/// Every stage in the game, in the order they are played.
+#[derive(Resource)]
+pub struct Campaign {
+    stages: Vec<Handle<Stage>>,
+    current: usize,
+}
+
+impl Campaign {
+    /// Index of the stage being played.
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    pub fn handle(&self) -> &Handle<Stage> {
+        &self.stages[self.current]
+    }
+
+    pub fn stage<'a>(&self, assets: &'a Assets<Stage>) -> Option<&'a Stage> {
+        assets.get(self.handle())
+    }
+
+    pub fn is_last(&self) -> bool {
+        self.current + 1 >= self.stages.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Move on to the next stage. Returns `false` if this was the last one.
+    fn advance(&mut self) -> bool {
+        if self.is_last() {
+            return false;
+        }
+        self.current += 1;
+        true
+    }
+}
+
+fn load_campaign(mut commands: Commands, server: Res<AssetServer>) {
+    commands.insert_resource(Campaign {
+        stages: STAGES.iter().map(|path| server.load(*path)).collect(),
+        current: 0,
+    });
+}
+
+fn restart(mut campaign: ResMut<Campaign>) {
+    campaign.current = 0;
+}
+
+/// Counts down the intro and the outro.
+#[derive(Resource)]
+struct PhaseTimer(Timer);
+
+/// Text shown between stages, cleared when the phase ends.
+#[derive(Component)]
+#[require(DespawnRestart)]
+struct StageCard;
+
+/// Waits for [`Interact`] to move on to the next stage.
+#[derive(Component)]
+struct TallyScreen;
+
+fn spawn_intro_card(
+    mut commands: Commands,
+    server: Res<AssetServer>,
+    campaign: Res<Campaign>,
+    stages: Res<Assets<Stage>>,
+) {
+    commands.insert_resource(PhaseTimer(Timer::from_seconds(INTRO_SECS, TimerMode::Once)));
+
+    let name = campaign
+        .stage(&stages)
+        .map(|stage| stage.name.clone())
+        .unwrap_or_default();
+    let lines = [
+        (20., 30., format!("Stage {}", campaign.current() + 1)),
+        (0., 20., name),
+    ];
+
+    for (y, size, text) in lines.into_iter() {
+        commands.spawn((
+            StageCard,
+            HIGH_RES_LAYER,
+            Text2d(text),
+            TextFont {
+                font_size: size,
+                font: server.load("fonts/joystix.otf"),
+                ..Default::default()
+            },
+            Transform::from_xyz(0., y, 500.),
+        ));
+    }
+}
+
+fn set_backdrop(
+    server: Res<AssetServer>,
+    campaign: Res<Campaign>,
+    stages: Res<Assets<Stage>>,
+    mut backdrop: Single<&mut Sprite, With<Backdrop>>,
+) {
+    if let Some(stage) = campaign.stage(&stages) {
+        backdrop.image = server.load(stage.background.clone());
+    }
+}
+
+fn wait_for_stage(mut commands: Commands, campaign: Res<Campaign>, stages: Res<Assets<Stage>>) {
+    if campaign.stage(&stages).is_some() {
+        commands.set_state(StagePhase::Intro);
+    }
+}
+
+fn end_intro(mut commands: Commands, time: Res<Time>, mut timer: ResMut<PhaseTimer>) {
+    timer.0.tick(time.delta());
+    if timer.0.finished() {
+        commands.set_state(StagePhase::Playing);
+    }
+}
+
+fn despawn_cards(mut commands: Commands, cards: Query<Entity, With<StageCard>>) {
+    for entity in cards.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// What the player did during the current stage, measured from where the run was at when the
+/// stage started.
+#[derive(Default, Resource)]
+pub struct StageTally {
+    start_time: f32,
+    start_kills: usize,
+    start_graze: usize,
+    misses: usize,
+}
+
+impl StageTally {
+    /// Times the player was hit this stage.
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+}
+
+fn start_tally(mut commands: Commands, stats: Res<Stats>, graze: Res<GrazeMeter>) {
+    commands.insert_resource(StageTally {
+        start_time: stats.time.elapsed_secs(),
+        start_kills: stats.kills,
+        start_graze: graze.total(),
+        misses: 0,
+    });
+}
+
+// Invincible players are never dealt damage, so every hit here is a miss.
+fn count_misses(
+    mut reader: EventReader<DamageDealt>,
+    player: Query<Entity, With<Player>>,
+    mut tally: ResMut<StageTally>,
+) {
+    if reader.read().any(|dealt| player.contains(dealt.entity)) {
+        tally.misses += 1;
+    }
+}
+
+fn clear_stage(mut commands: Commands, mut reader: EventReader<BossDefeated>) {
+    if reader.read().count() > 0 {
+        commands.set_state(StagePhase::Outro);
+    }
+}
+
+fn start_outro(mut commands: Commands) {
+    commands.insert_resource(PhaseTimer(Timer::from_seconds(OUTRO_SECS, TimerMode::Once)));
+}
+
+fn show_tally(
+    mut commands: Commands,
+    server: Res<AssetServer>,
+    time: Res<Time>,
+    mut timer: ResMut<PhaseTimer>,
+    campaign: Res<Campaign>,
+    tally: Res<StageTally>,
+    stats: Res<Stats>,
+    graze: Res<GrazeMeter>,
+    mut writer: EventWriter<PointEvent>,
+) {
+    timer.0.tick(time.delta());
+    if !timer.0.just_finished() {
+        return;
+    }
+
+    let stage = campaign.current() + 1;
+    let grazed = graze.total() - tally.start_graze;
+    let clear_bonus = CLEAR_BONUS * stage;
+    let graze_bonus = GRAZE_BONUS * grazed;
+    let no_miss_bonus = if tally.misses == 0 { NO_MISS_BONUS } else { 0 };
+
+    writer.write(PointEvent::new(
+        clear_bonus + graze_bonus + no_miss_bonus,
+        Vec2::new(0., -crate::HEIGHT / 4.),
+    ));
+
+    commands.spawn((StageCard, TallyScreen));
+
+    commands.spawn((
+        StageCard,
+        HIGH_RES_LAYER,
+        Text2d(format!("Stage {stage} Clear!")),
+        TextFont {
+            font_size: 30.,
+            font: server.load("fonts/joystix.otf"),
+            ..Default::default()
+        },
+        Transform::from_xyz(0., 80., 500.),
+    ));
+
+    let misses = if tally.misses == 0 {
+        format!("No Miss: +{no_miss_bonus}")
+    } else {
+        format!("Misses: {}", tally.misses)
+    };
+    let continue_text = if campaign.is_last() {
+        "[ Finish ]"
+    } else {
+        "[ Continue ]"
+    };
+    let lines = [
+        (
+            40.,
+            format!("Time: {:.2}", stats.time.elapsed_secs() - tally.start_time),
+        ),
+        (20., format!("Kills: {}", stats.kills - tally.start_kills)),
+        (0., format!("Graze: {grazed} +{graze_bonus}")),
+        (-20., misses),
+        (-40., format!("Clear: +{clear_bonus}")),
+        (-crate::HEIGHT / 2., continue_text.into()),
+    ];
+
+    for (y, text) in lines.into_iter() {
+        commands.spawn((
+            StageCard,
+            HIGH_RES_LAYER,
+            Text2d(text),
+            TextFont {
+                font_size: 20.,
+                font: server.load("fonts/joystix.otf"),
+                ..Default::default()
+            },
+            Transform::from_xyz(0., y, 500.),
+        ));
+    }
+
+    commands.spawn((
+        StageCard,
+        Sprite {
+            rect: Some(Rect::from_center_size(
+                Vec2::ZERO,
+                Vec2::new(crate::WIDTH, crate::HEIGHT),
+            )),
+            color: Color::linear_rgba(0., 0., 0., 0.9),
+            ..Default::default()
+        },
+        Transform::from_xyz(0., 0., 499.),
+    ));
+}
+
+fn next_stage(
+    _: Trigger<Fired<Interact>>,
+    _tally: Single<&TallyScreen>,
+    mut commands: Commands,
+    mut campaign: ResMut<Campaign>,
+    cards: Query<Entity, With<StageCard>>,
+) {
+    if campaign.advance() {
+        commands.set_state(StagePhase::Loading);
+    } else {
+        for entity in cards.iter() {
+            commands.entity(entity).despawn();
+        }
+        commands.queue(|world: &mut World| world.run_system_once(end::show_win_screen));
+    }
+}
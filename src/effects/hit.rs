@@ -0,0 +1,295 @@
+//! Feedback for entities taking damage, configured per entity type with [`HitFeedback`].
+//!
+//! Every [`DamageDealt`] on an entity with [`HitFeedback`] flashes its sprites white, knocks it
+//! back and shakes the screen, and big hits stop time for a moment. Damage numbers and sounds are
+//! gathered for [`POPUP_SECS`], so that continuous damage like lasers does not spam them.
+use crate::{
+    Avian, GameState, RESOLUTION_SCALE,
+    color::HexColor,
+    health::{DamageDealt, HealthSet},
+    points,
+    text::flash_text,
+    tween::TimeMult,
+};
+use bevy::prelude::*;
+use bevy_optix::shake::TraumaCommands;
+use bevy_seedling::prelude::*;
+use std::time::Duration;
+
+/// Seconds between damage numbers and sounds on the same entity.
+pub const POPUP_SECS: f32 = 0.15;
+/// Sprite tint while flashing. Channels above 1 wash the sprite out to white.
+const FLASH_COLOR: Color = Color::linear_rgb(8., 8., 8.);
+const NUMBER_COLOR: HexColor = HexColor(0xd41e3c);
+/// Relative speed of time during a hit-stop.
+const HIT_STOP_SPEED: f32 = 0.05;
+
+pub struct HitFeedbackPlugin;
+
+impl Plugin for HitFeedbackPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActiveHitStop>()
+            .add_systems(OnEnter(GameState::Restart), restart)
+            .add_systems(
+                Avian,
+                hit_feedback
+                    .after(HealthSet)
+                    .run_if(in_state(GameState::Game)),
+            )
+            .add_systems(Update, (update_flashes, update_popups, update_hit_stop));
+    }
+}
+
+/// Slow time to a crawl for a moment.
+#[derive(Debug, Clone, Copy)]
+pub struct HitStop {
+    /// Hits dealing less damage are not big enough. Killing blows always are.
+    pub min_damage: f32,
+    pub millis: u64,
+}
+
+/// How an entity reacts to taking damage.
+#[derive(Debug, Clone, Copy, Component)]
+#[require(PendingHits)]
+pub struct HitFeedback {
+    /// Seconds the entity's sprites flash white.
+    pub flash: f32,
+    /// Pixels the entity is pushed away from whatever hit it.
+    pub knockback: f32,
+    /// Direction of the knockback when the source of the damage is unknown or gone.
+    pub knockback_dir: Vec2,
+    pub trauma: f32,
+    pub hit_stop: Option<HitStop>,
+    /// Pop up the damage taken above the entity.
+    pub numbers: bool,
+    pub sounds: &'static [&'static str],
+    pub volume: f32,
+}
+
+impl HitFeedback {
+    pub const ENEMY: Self = Self {
+        flash: 0.06,
+        knockback: 1.,
+        // most hits come from the player's shots
+        knockback_dir: Vec2::Y,
+        trauma: 0.,
+        hit_stop: None,
+        numbers: true,
+        sounds: &["audio/sfx/blop.wav"],
+        volume: 0.1,
+    };
+
+    pub const BOSS: Self = Self {
+        knockback: 0.,
+        hit_stop: Some(HitStop {
+            min_damage: 10.,
+            millis: 150,
+        }),
+        ..Self::ENEMY
+    };
+
+    /// The player already flickers while [`Invincible`](crate::health::Invincible).
+    pub const PLAYER: Self = Self {
+        flash: 0.,
+        knockback: 4.,
+        knockback_dir: Vec2::NEG_Y,
+        trauma: 0.15,
+        hit_stop: Some(HitStop {
+            min_damage: 0.,
+            millis: 80,
+        }),
+        numbers: false,
+        sounds: &["audio/sfx/melee.wav", "audio/sfx/player_damage.wav"],
+        volume: 0.25,
+    };
+}
+
+/// Damage gathered since the last damage number.
+#[derive(Default, Component)]
+struct PendingHits {
+    damage: f32,
+    cooldown: f32,
+}
+
+/// Flashes the entity's sprites white until the timer finishes, then gives them back their
+/// colours.
+#[derive(Component)]
+struct HitFlash {
+    timer: Timer,
+    /// Each sprite's colour from before the flash, taken when it starts.
+    previous: Option<Vec<(Entity, Color)>>,
+}
+
+impl HitFlash {
+    fn new(secs: f32) -> Self {
+        Self {
+            timer: Timer::from_seconds(secs, TimerMode::Once),
+            previous: None,
+        }
+    }
+}
+
+/// The hit-stop in progress and the time speed to restore after it.
+#[derive(Default, Resource)]
+struct ActiveHitStop(Option<(Timer, f32)>);
+
+fn restart(mut commands: Commands) {
+    commands.insert_resource(ActiveHitStop::default());
+}
+
+fn hit_feedback(
+    mut commands: Commands,
+    mut reader: EventReader<DamageDealt>,
+    mut targets: Query<(
+        &HitFeedback,
+        &mut PendingHits,
+        &mut Transform,
+        &GlobalTransform,
+        Option<&mut HitFlash>,
+    )>,
+    sources: Query<&GlobalTransform>,
+    mut hit_stop: ResMut<ActiveHitStop>,
+    mut time: ResMut<TimeMult>,
+) {
+    for dealt in reader.read() {
+        if dealt.amount <= 0. {
+            continue;
+        }
+        let Ok((feedback, mut pending, mut transform, gt, flash)) = targets.get_mut(dealt.entity)
+        else {
+            continue;
+        };
+
+        if feedback.flash > 0. {
+            match flash {
+                // keep the colours from before the first hit
+                Some(mut flash) => flash.timer.reset(),
+                None => {
+                    commands
+                        .entity(dealt.entity)
+                        .insert(HitFlash::new(feedback.flash));
+                }
+            }
+        }
+
+        if feedback.knockback > 0. {
+            let position = gt.translation().xy();
+            let dir = dealt
+                .source
+                .and_then(|source| sources.get(source).ok())
+                .and_then(|source| (position - source.translation().xy()).try_normalize())
+                .unwrap_or(feedback.knockback_dir);
+            transform.translation += (dir * feedback.knockback).extend(0.);
+        }
+
+        if feedback.trauma > 0. {
+            commands.add_trauma(feedback.trauma);
+        }
+
+        if let Some(stop) = feedback.hit_stop {
+            if dealt.killed || dealt.amount >= stop.min_damage {
+                let duration = Duration::from_millis(stop.millis);
+                match &mut hit_stop.0 {
+                    Some((timer, _)) => {
+                        *timer = Timer::new(duration.max(timer.remaining()), TimerMode::Once);
+                    }
+                    None => {
+                        hit_stop.0 = Some((Timer::new(duration, TimerMode::Once), time.0));
+                        time.0 = HIT_STOP_SPEED;
+                    }
+                }
+            }
+        }
+
+        pending.damage += dealt.amount;
+    }
+}
+
+fn update_flashes(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut flashes: Query<(Entity, &mut HitFlash, Option<&Children>)>,
+    mut sprites: Query<&mut Sprite>,
+) {
+    for (entity, mut flash, children) in flashes.iter_mut() {
+        let flash = &mut *flash;
+        let previous = flash.previous.get_or_insert_with(|| {
+            std::iter::once(entity)
+                .chain(children.into_iter().flatten().copied())
+                .filter_map(|target| Some((target, sprites.get(target).ok()?.color)))
+                .collect()
+        });
+
+        let finished = flash.timer.tick(time.delta()).finished();
+        if finished {
+            commands.entity(entity).remove::<HitFlash>();
+        }
+
+        for &(target, color) in previous.iter() {
+            if let Ok(mut sprite) = sprites.get_mut(target) {
+                sprite.color = if finished { color } else { FLASH_COLOR };
+            }
+        }
+    }
+}
+
+fn update_popups(
+    mut commands: Commands,
+    server: Res<AssetServer>,
+    time: Res<Time>,
+    mut hits: Query<(&HitFeedback, &mut PendingHits, &GlobalTransform)>,
+) {
+    for (feedback, mut pending, gt) in hits.iter_mut() {
+        pending.cooldown -= time.delta_secs();
+        if pending.damage <= 0. || pending.cooldown > 0. {
+            continue;
+        }
+
+        if feedback.numbers {
+            let damage = if pending.damage.fract() < 0.05 {
+                format!("{:.0}", pending.damage)
+            } else {
+                format!("{:.1}", pending.damage)
+            };
+            flash_text(
+                &mut commands,
+                &server,
+                damage,
+                16.,
+                ((gt.translation().xy() + Vec2::Y * 8.) * RESOLUTION_SCALE)
+                    .extend(points::POINT_TEXT_Z + 1.),
+                NUMBER_COLOR,
+            );
+        }
+
+        for sound in feedback.sounds {
+            commands.spawn((
+                SamplePlayer::new(server.load(*sound)),
+                PitchRange(0.98..1.02),
+                PlaybackSettings {
+                    volume: Volume::Linear(feedback.volume),
+                    ..PlaybackSettings::ONCE
+                },
+            ));
+        }
+
+        pending.damage = 0.;
+        pending.cooldown = POPUP_SECS;
+    }
+}
+
+fn update_hit_stop(
+    // virtual time is what is being stopped
+    time: Res<Time<Real>>,
+    mut hit_stop: ResMut<ActiveHitStop>,
+    mut mult: ResMut<TimeMult>,
+) {
+    let Some((timer, restore)) = &mut hit_stop.0 else {
+        return;
+    };
+
+    if timer.tick(time.delta()).finished() {
+        mult.0 = *restore;
+        hit_stop.0 = None;
+    }
+}
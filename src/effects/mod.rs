@@ -7,38 +7,44 @@ use bevy::render::render_resource::{AsBindGroup, ShaderRef, ShaderType};
 use bevy::sprite::{AlphaMode2d, Material2d, Material2dPlugin};
 use bevy_enoki::prelude::*;
 use bevy_seedling::prelude::*;
+use serde::Deserialize;
+
+pub mod hit;
 
 pub struct EffectsPlugin;
 
 impl Plugin for EffectsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(Material2dPlugin::<Lightning>::default())
-            .add_event::<SpawnExplosion>()
-            //.add_systems(Startup, lightning)
-            .add_systems(
-                Update,
-                (
-                    spawn_explosions,
-                    write_explosions,
-                    (spawn_blasters, update_blasters).chain(),
-                ),
-            )
-            .register_layout(
-                "fire_sparks.png",
-                TextureAtlasLayout::from_grid(UVec2::splat(96), 4, 5, None, None),
-            )
-            .register_layout(
-                "sparks.png",
-                TextureAtlasLayout::from_grid(UVec2::splat(150), 5, 6, None, None),
-            )
-            .register_layout(
-                "explosion2.png",
-                TextureAtlasLayout::from_grid(UVec2::splat(64), 12, 9, None, None),
-            )
-            .register_layout(
-                "explosion3.png",
-                TextureAtlasLayout::from_grid(UVec2::splat(64), 12, 9, None, None),
-            );
+        app.add_plugins((
+            Material2dPlugin::<Lightning>::default(),
+            hit::HitFeedbackPlugin,
+        ))
+        .add_event::<SpawnExplosion>()
+        //.add_systems(Startup, lightning)
+        .add_systems(
+            Update,
+            (
+                spawn_explosions,
+                write_explosions,
+                (spawn_blasters, update_blasters).chain(),
+            ),
+        )
+        .register_layout(
+            "fire_sparks.png",
+            TextureAtlasLayout::from_grid(UVec2::splat(96), 4, 5, None, None),
+        )
+        .register_layout(
+            "sparks.png",
+            TextureAtlasLayout::from_grid(UVec2::splat(150), 5, 6, None, None),
+        )
+        .register_layout(
+            "explosion2.png",
+            TextureAtlasLayout::from_grid(UVec2::splat(64), 12, 9, None, None),
+        )
+        .register_layout(
+            "explosion3.png",
+            TextureAtlasLayout::from_grid(UVec2::splat(64), 12, 9, None, None),
+        );
     }
 }
 
@@ -60,7 +66,7 @@ pub struct SpawnExplosion {
     pub explosion: Explosion,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Component)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component, Deserialize)]
 pub enum Explosion {
     Big,
     Small,
@@ -0,0 +1,283 @@
+//! Enemy stats, kept in one table instead of each enemy's `#[require(...)]` list.
+//!
+//! The table is loaded from `enemies.archetypes.ron` and maps archetype names to [`EnemyStats`].
+//! Spawning an enemy with an [`EnemyArchetype`] inserts the stats under that name:
+//!
+//! ```ron
+//! {
+//!     "orb_slinger": (
+//!         health: 20.0,
+//!         armour: 0.1,
+//!         resistances: Some((explosive: 0.5)),
+//!         collider: Circle(6.0),
+//!         sprites: [(size: TwentyFour, cell: (0, 1))],
+//!         drops: Some((parts: Static(8), shield: Static(8))),
+//!         explosion: Big,
+//!         score: 50,
+//!         emitter: Some("spiral_orb"),
+//!     ),
+//! }
+//! ```
+//!
+//! Emitters are named in the [`EmitterRegistry`], since their components are not data.
+use super::{
+    Drops, Score, Trauma, arcs::ArcsEmitter, buckshot, crisscross::CrisscrossEmitter, swarm, waller,
+};
+use crate::{
+    assets::{self, RonAssetAppExt},
+    auto_collider::ImageCollider,
+    bullet::{emitter::SpiralOrbEmitter, pattern::PatternEmitter},
+    effects::Explosion,
+    health::{Armour, Health, Resistances},
+    sprites::{CellSize, CellSprite, MultiSprite, SpriteBundle},
+};
+use avian2d::prelude::*;
+use bevy::{platform::collections::HashMap, prelude::*};
+use serde::Deserialize;
+
+const TABLE_PATH: &str = "enemies.archetypes.ron";
+/// Given to enemies with an unknown archetype, so they can still be destroyed.
+const FALLBACK_HEALTH: f32 = 10.;
+const FALLBACK_RADIUS: f32 = 8.;
+
+pub struct ArchetypePlugin;
+
+impl Plugin for ArchetypePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_ron_asset::<ArchetypeTable>(&["archetypes.ron"])
+            .insert_resource(EmitterRegistry::builtin())
+            .add_systems(Startup, load_archetypes)
+            .add_observer(apply_archetype);
+    }
+}
+
+/// Every enemy's stats, by archetype name.
+#[derive(Debug, Clone, Asset, TypePath, Deserialize)]
+#[serde(transparent)]
+pub struct ArchetypeTable(pub HashMap<String, EnemyStats>);
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EnemyStats {
+    pub health: f32,
+    #[serde(default)]
+    pub armour: f32,
+    #[serde(default)]
+    pub resistances: Option<Resistances>,
+    pub collider: ArchetypeCollider,
+    /// Layers of the enemy's sprite. Enemies with animated sprites set them up themselves.
+    #[serde(default)]
+    pub sprites: Vec<ArchetypeSprite>,
+    #[serde(default)]
+    drops: Option<Drops>,
+    pub explosion: Explosion,
+    /// Points for destroying the enemy.
+    #[serde(default = "default_score")]
+    pub score: usize,
+    #[serde(default = "default_trauma")]
+    pub trauma: f32,
+    /// Name in the [`EmitterRegistry`]. Enemies driven by an
+    /// [`EnemyBrain`](super::brain::EnemyBrain) leave this out.
+    #[serde(default)]
+    pub emitter: Option<String>,
+}
+
+fn default_score() -> usize {
+    Score::default().0
+}
+
+fn default_trauma() -> f32 {
+    Trauma::default().0
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub enum ArchetypeCollider {
+    Circle(f32),
+    Rectangle(f32, f32),
+    /// Fit to the sprite, see [`ImageCollider`].
+    Image,
+}
+
+impl ArchetypeCollider {
+    fn insert(self, commands: &mut EntityCommands) {
+        match self {
+            Self::Circle(radius) => commands.insert(Collider::circle(radius)),
+            Self::Rectangle(width, height) => commands.insert(Collider::rectangle(width, height)),
+            Self::Image => commands.insert(ImageCollider),
+        };
+    }
+}
+
+/// One cell of a sprite sheet.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct ArchetypeSprite {
+    #[serde(default)]
+    pub sheet: SpriteSheet,
+    pub size: CellSize,
+    pub cell: UVec2,
+    #[serde(default)]
+    pub z: f32,
+}
+
+impl ArchetypeSprite {
+    fn cell_sprite(&self) -> CellSprite {
+        CellSprite {
+            path: self.sheet.path(),
+            size: self.size,
+            cell: self.cell,
+            z: self.z,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+pub enum SpriteSheet {
+    /// `ships.png`
+    #[default]
+    Ships,
+    /// The ships of the space shooter asset pack.
+    Shooters,
+}
+
+impl SpriteSheet {
+    pub fn path(self) -> &'static str {
+        match self {
+            Self::Ships => "ships.png",
+            Self::Shooters => assets::SHIPS_PATH,
+        }
+    }
+}
+
+/// The loaded [`ArchetypeTable`].
+#[derive(Resource)]
+pub struct EnemyArchetypes(Handle<ArchetypeTable>);
+
+impl EnemyArchetypes {
+    pub fn get<'a>(
+        &self,
+        tables: &'a Assets<ArchetypeTable>,
+        name: &str,
+    ) -> Option<&'a EnemyStats> {
+        tables.get(&self.0).and_then(|table| table.0.get(name))
+    }
+
+    pub fn is_loaded(&self, tables: &Assets<ArchetypeTable>) -> bool {
+        tables.contains(&self.0)
+    }
+}
+
+fn load_archetypes(mut commands: Commands, server: Res<AssetServer>) {
+    commands.insert_resource(EnemyArchetypes(server.load(TABLE_PATH)));
+}
+
+/// Inserts the stats of the named archetype when added to an enemy.
+#[derive(Debug, Clone, Copy, Component)]
+pub struct EnemyArchetype(pub &'static str);
+
+pub type EmitterConstructor = fn(&mut EntityCommands);
+
+/// Maps the emitter names used in the archetype table to the components they insert.
+#[derive(Default, Resource)]
+pub struct EmitterRegistry(HashMap<&'static str, EmitterConstructor>);
+
+impl EmitterRegistry {
+    pub fn register(&mut self, name: &'static str, constructor: EmitterConstructor) -> &mut Self {
+        if self.0.insert(name, constructor).is_some() {
+            warn!("emitter `{name}` was registered twice");
+        }
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<EmitterConstructor> {
+        self.0.get(name).copied()
+    }
+
+    fn builtin() -> Self {
+        let mut registry = Self::default();
+        registry
+            .register("spiral_orb", |enemy| {
+                enemy.insert(SpiralOrbEmitter);
+            })
+            .register("swarm", |enemy| {
+                enemy.insert(PatternEmitter::new(swarm::PATTERN));
+            })
+            .register("buckshot", |enemy| {
+                enemy.insert(PatternEmitter::new(buckshot::PATTERN));
+            })
+            .register("wall", |enemy| {
+                enemy.insert(PatternEmitter::new(waller::PATTERN));
+            })
+            .register("arcs", |enemy| {
+                enemy.insert(ArcsEmitter);
+            })
+            .register("crisscross", |enemy| {
+                enemy.insert(CrisscrossEmitter::default());
+            });
+        registry
+    }
+}
+
+fn apply_archetype(
+    trigger: Trigger<OnAdd, EnemyArchetype>,
+    mut commands: Commands,
+    enemies: Query<&EnemyArchetype>,
+    archetypes: Res<EnemyArchetypes>,
+    tables: Res<Assets<ArchetypeTable>>,
+    emitters: Res<EmitterRegistry>,
+) {
+    let Ok(archetype) = enemies.get(trigger.target()) else {
+        return;
+    };
+    let Some(stats) = archetypes.get(&tables, archetype.0) else {
+        error!("unknown enemy archetype `{}`", archetype.0);
+        // without health the enemy could never be cleared, holding up the stage
+        commands.entity(trigger.target()).insert((
+            Health::full(FALLBACK_HEALTH),
+            Collider::circle(FALLBACK_RADIUS),
+        ));
+        return;
+    };
+
+    let mut enemy = commands.entity(trigger.target());
+    enemy.insert((
+        Health::full(stats.health),
+        stats.explosion,
+        Score(stats.score),
+        Trauma(stats.trauma),
+    ));
+    stats.collider.insert(&mut enemy);
+
+    if stats.armour > 0. {
+        enemy.insert(Armour(stats.armour));
+    }
+    if let Some(resistances) = stats.resistances {
+        enemy.insert(resistances);
+    }
+
+    match stats.sprites.as_slice() {
+        [] => {}
+        [sprite] => {
+            enemy.insert(sprite.cell_sprite());
+        }
+        sprites => {
+            enemy.insert(SpriteBundle::new(
+                sprites
+                    .iter()
+                    .map(|sprite| MultiSprite::Static(sprite.cell_sprite())),
+            ));
+        }
+    }
+
+    if let Some(drops) = &stats.drops {
+        enemy.insert(drops.clone());
+    }
+
+    if let Some(name) = &stats.emitter {
+        match emitters.get(name) {
+            Some(emitter) => emitter(&mut enemy),
+            None => error!(
+                "unknown emitter `{name}` for enemy archetype `{}`",
+                archetype.0
+            ),
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use super::Enemy;
-use super::Trauma;
+use super::archetype::EnemyArchetype;
 use super::formation::Formation;
 use super::formation::Platoon;
 use crate::bullet::BasicBullet;
@@ -11,15 +11,9 @@ use crate::bullet::emitter::EmitterDelay;
 use crate::bullet::emitter::RotateBullet;
 use crate::bullet::emitter::ShootEmitter;
 use crate::bullet::emitter::Target;
+use crate::bullet::emitter::{BulletModifiers, Rate};
 use crate::enemy::FaceVelocity;
 use crate::tween::DespawnTweenFinish;
-use crate::{
-    auto_collider::ImageCollider,
-    bullet::emitter::{BulletModifiers, Rate},
-    effects::Explosion,
-    health::Health,
-};
-use avian2d::prelude::Collider;
 use bevy::color::palettes::css::GRAY;
 use bevy::prelude::*;
 use bevy_optix::debug::DebugCircle;
@@ -35,16 +29,12 @@ const BULLET_SPEED: f32 = 50.;
 #[derive(Default, Component)]
 #[require(
     Enemy,
-    ArcsEmitter,
-    Health::full(50.),
+    EnemyArchetype("arcs"),
     DebugCircle::color(12., GRAY),
-    Collider::circle(12.),
     BulletModifiers {
         rate: Rate::Factor(0.2),
         ..Default::default()
     },
-    Trauma(0.04),
-    Explosion::Small,
     FaceVelocity,
 )]
 pub struct Arcs;
@@ -0,0 +1,276 @@
+//! Declarative enemy behaviour.
+//!
+//! An [`EnemyBrain`] moves an enemy between a handful of [`BrainState`]s. Each state has a
+//! [`Behaviour`]: how the enemy moves, which emitter it shoots with, and the [`Exit`]s that lead to
+//! other states.
+//!
+//! ```ignore
+//! EnemyBrain::new([
+//!     (BrainState::Enter, Behaviour::new().exit(Exit::After(1.5), BrainState::Attack)),
+//!     (
+//!         BrainState::Attack,
+//!         Behaviour::new()
+//!             .emitter(PatternEmitter::new("patterns/verger.pattern.ron"))
+//!             .exit(Exit::Shots(20), BrainState::Reposition),
+//!     ),
+//!     ...
+//! ])
+//! ```
+use super::path::{Path, PathCurve};
+use crate::{GameState, bullet::emitter::Shots, health::Health, player::Player};
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use std::sync::Arc;
+
+pub struct BrainPlugin;
+
+impl Plugin for BrainPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, update_brains.run_if(in_state(GameState::Game)));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BrainState {
+    /// Moving into place.
+    Enter,
+    Attack,
+    /// Moving somewhere else on screen.
+    Reposition,
+    /// Leaving the screen.
+    Retreat,
+}
+
+/// What an enemy does while in a [`BrainState`].
+#[derive(Default, Clone)]
+pub struct Behaviour {
+    movement: Movement,
+    emitter: Option<EmitterCommands>,
+    exits: Vec<(Exit, BrainState)>,
+}
+
+#[derive(Clone)]
+struct EmitterCommands {
+    insert: Arc<dyn Fn(&mut EntityCommands) + Send + Sync>,
+    remove: Arc<dyn Fn(&mut EntityCommands) + Send + Sync>,
+}
+
+impl Behaviour {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn movement(mut self, movement: Movement) -> Self {
+        self.movement = movement;
+        self
+    }
+
+    /// Inserted on the enemy when the state is entered, and removed when it is left.
+    pub fn emitter<B: Bundle + Clone>(mut self, emitter: B) -> Self {
+        self.emitter = Some(EmitterCommands {
+            insert: Arc::new(move |commands| {
+                commands.insert(emitter.clone());
+            }),
+            remove: Arc::new(|commands| {
+                commands.remove::<B>();
+            }),
+        });
+        self
+    }
+
+    /// Move to `state` once `exit` holds. Exits are checked in the order they were added.
+    pub fn exit(mut self, exit: Exit, state: BrainState) -> Self {
+        self.exits.push((exit, state));
+        self
+    }
+}
+
+#[derive(Default, Clone)]
+pub enum Movement {
+    /// Leave the enemy's velocity as it is.
+    #[default]
+    Keep,
+    /// Stand still.
+    Hold,
+    Velocity(Vec2),
+    /// Follow a path at some speed, stopping at its end.
+    Path(PathCurve, f32),
+}
+
+/// A condition for leaving a [`BrainState`].
+#[derive(Debug, Clone, Copy)]
+pub enum Exit {
+    /// Seconds spent in the state.
+    After(f32),
+    /// Health dropped to this fraction of the maximum.
+    Health(f32),
+    /// Shots the state's emitter has taken. A shot is one firing, however many bullets it spawns.
+    Shots(usize),
+    /// The player came within this distance.
+    PlayerWithin(f32),
+    /// The player moved further away than this distance.
+    PlayerBeyond(f32),
+    /// The state's [`Movement::Path`] reached its end.
+    Arrived,
+}
+
+/// Everything an [`Exit`] is checked against.
+struct Senses {
+    elapsed: f32,
+    health: Option<Health>,
+    shots: usize,
+    player_distance: Option<f32>,
+    moving: bool,
+}
+
+impl Exit {
+    fn holds(&self, senses: &Senses) -> bool {
+        match *self {
+            Self::After(secs) => senses.elapsed >= secs,
+            Self::Health(fraction) => senses
+                .health
+                .is_some_and(|health| health.current() <= health.max() * fraction),
+            Self::Shots(shots) => senses.shots >= shots,
+            Self::PlayerWithin(distance) => senses
+                .player_distance
+                .is_some_and(|player| player <= distance),
+            Self::PlayerBeyond(distance) => senses
+                .player_distance
+                .is_some_and(|player| player > distance),
+            Self::Arrived => !senses.moving,
+        }
+    }
+}
+
+/// A state machine driving an enemy's movement and emitters.
+///
+/// Starts in the first state it is given.
+#[derive(Clone, Component)]
+pub struct EnemyBrain {
+    behaviours: Arc<[(BrainState, Behaviour)]>,
+    state: BrainState,
+    elapsed: f32,
+    entered: bool,
+}
+
+impl EnemyBrain {
+    /// # Panics
+    ///
+    /// Panics if there are no states.
+    pub fn new(behaviours: impl IntoIterator<Item = (BrainState, Behaviour)>) -> Self {
+        let behaviours = behaviours.into_iter().collect::<Arc<[_]>>();
+        let state = behaviours
+            .first()
+            .expect("a brain needs at least one state")
+            .0;
+        Self {
+            behaviours,
+            state,
+            elapsed: 0.,
+            entered: false,
+        }
+    }
+
+    pub fn state(&self) -> BrainState {
+        self.state
+    }
+
+    /// Seconds spent in the current state.
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    fn behaviour(&self, state: BrainState) -> Option<&Behaviour> {
+        self.behaviours
+            .iter()
+            .find_map(|(s, behaviour)| (*s == state).then_some(behaviour))
+    }
+
+    fn enter(&self, commands: &mut EntityCommands) {
+        let Some(behaviour) = self.behaviour(self.state) else {
+            error!("enemy brain has no `{:?}` state", self.state);
+            return;
+        };
+
+        commands.insert(Shots::default());
+        match &behaviour.movement {
+            Movement::Keep => {}
+            Movement::Hold => {
+                commands.insert(LinearVelocity::ZERO);
+            }
+            Movement::Velocity(velocity) => {
+                commands.insert(LinearVelocity(*velocity));
+            }
+            Movement::Path(curve, speed) => {
+                commands.insert((
+                    LinearVelocity::ZERO,
+                    Path::new(curve.clone(), *speed).stopping(),
+                ));
+            }
+        }
+        if let Some(emitter) = &behaviour.emitter {
+            (emitter.insert)(commands);
+        }
+    }
+
+    fn exit(&self, commands: &mut EntityCommands) {
+        let Some(behaviour) = self.behaviour(self.state) else {
+            return;
+        };
+
+        if let Movement::Path(..) = behaviour.movement {
+            commands.remove::<Path>();
+        }
+        if let Some(emitter) = &behaviour.emitter {
+            (emitter.remove)(commands);
+        }
+    }
+}
+
+fn update_brains(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut brains: Query<(
+        Entity,
+        &mut EnemyBrain,
+        &GlobalTransform,
+        Option<&Health>,
+        Option<&Shots>,
+        Has<Path>,
+    )>,
+    player: Option<Single<&GlobalTransform, With<Player>>>,
+) {
+    let player = player.map(|player| player.translation().xy());
+
+    for (entity, mut brain, transform, health, shots, moving) in brains.iter_mut() {
+        if !brain.entered {
+            brain.entered = true;
+            brain.enter(&mut commands.entity(entity));
+            continue;
+        }
+
+        brain.elapsed += time.delta_secs();
+
+        let senses = Senses {
+            elapsed: brain.elapsed,
+            health: health.copied(),
+            shots: shots.map(Shots::get).unwrap_or_default(),
+            player_distance: player.map(|player| player.distance(transform.translation().xy())),
+            moving,
+        };
+        let Some(next) = brain.behaviour(brain.state).and_then(|behaviour| {
+            behaviour
+                .exits
+                .iter()
+                .find_map(|(exit, state)| exit.holds(&senses).then_some(*state))
+        }) else {
+            continue;
+        };
+
+        let mut commands = commands.entity(entity);
+        brain.exit(&mut commands);
+        brain.state = next;
+        brain.elapsed = 0.;
+        brain.enter(&mut commands);
+    }
+}
@@ -2,6 +2,7 @@ use std::f32::consts::PI;
 
 use super::Enemy;
 use super::LowHealthEffects;
+use super::archetype::EnemyArchetype;
 use super::formation::Formation;
 use super::formation::Platoon;
 use super::formation::animate_entrance_with;
@@ -18,13 +19,12 @@ use crate::bullet::emitter::ORB_SPEED;
 use crate::bullet::emitter::PulseTimer;
 use crate::bullet::emitter::ShootEmitter;
 use crate::bullet::emitter::Target;
+use crate::bullet::motion::{Acceleration, BulletMotion};
 use crate::sprites::CellSize;
 use crate::sprites::MultiSprite;
 use crate::sprites::SpriteBehavior;
 use crate::sprites::SpriteBundle;
-use crate::{
-    bullet::emitter::BulletModifiers, effects::Explosion, health::Health, sprites::CellSprite,
-};
+use crate::{bullet::emitter::BulletModifiers, sprites::CellSprite};
 use avian2d::prelude::*;
 use bevy::prelude::*;
 
@@ -32,16 +32,18 @@ const WAIT: f32 = 1.25;
 const SHOT: f32 = 0.15;
 const WAVES: usize = 5;
 const BULLET_SPEED: f32 = ORB_SPEED;
+/// Plus orbs brake down to a crawl and linger.
+const PLUS_DECELERATION: f32 = 50.;
+const PLUS_MIN_SPEED: f32 = 20.;
+/// Cross orbs swirl, alternating direction every wave.
+const CROSS_CURVE: f32 = 0.3;
 
 #[derive(Default, Clone, Copy, Component)]
 #[require(
     Enemy,
-    Collider::rectangle(12., 12.),
+    EnemyArchetype("crisscross"),
     SpriteBundle = Self::sprites(),
-    Health::full(15.),
     LowHealthEffects,
-    CrisscrossEmitter,
-    Explosion::Big,
 )]
 pub struct CrissCross;
 
@@ -119,7 +121,7 @@ impl ShootEmitter for CrisscrossEmitter {
         &self,
         mut commands: BulletCommands,
         transform: Transform,
-        _ctx: EmitterCtx<Self::Timer>,
+        ctx: EmitterCtx<Self::Timer>,
     ) {
         let angle_offset = match self.0 {
             CrisscrossState::Cross => std::f32::consts::PI / 4.,
@@ -130,8 +132,20 @@ impl ShootEmitter for CrisscrossEmitter {
             let angle = (angle as f32 / bullets as f32) * std::f32::consts::TAU + angle_offset;
             let mut entity = commands.spawn_angled(angle, transform);
             match self.0 {
-                CrisscrossState::Plus => entity.insert(RedOrb),
-                CrisscrossState::Cross => entity.insert(BlueOrb),
+                CrisscrossState::Plus => {
+                    entity.insert(RedOrb).accelerate(
+                        Acceleration::new(-PLUS_DECELERATION * ctx.mods.speed)
+                            .clamped(PLUS_MIN_SPEED * ctx.mods.speed, f32::MAX),
+                    );
+                }
+                CrisscrossState::Cross => {
+                    let curve = if ctx.timer.current_pulse() % 2 == 0 {
+                        CROSS_CURVE
+                    } else {
+                        -CROSS_CURVE
+                    };
+                    entity.insert(BlueOrb).curving(curve);
+                }
             };
         }
     }
@@ -0,0 +1,263 @@
+//! Debug panel for scrubbing through the current stage.
+//!
+//! | Key       | Action                                 |
+//! |-----------|----------------------------------------|
+//! | `F1`      | Show or hide the panel                 |
+//! | `[` `]`   | Move the cursor                        |
+//! | `F2`      | Jump to the entry under the cursor     |
+//! | `F3`      | Loop the entry under the cursor        |
+//! | `F4`      | Pause                                  |
+//! | `-` `=`   | Slow down or speed up time             |
+//!
+//! The controls only work while the panel is shown.
+//!
+//! Jumping and looping only rebuild the [`WaveTimeline`]. Power, points, and misses stay as they
+//! are, so branches after the cursor are taken on the run so far rather than on what a real run
+//! would have reached there. Branches before the cursor are not replayed, and signals that were
+//! not waited on yet are dropped.
+use super::{
+    formation::{FormationEntity, Units},
+    stage::{FormationRegistry, Stage, WaveEntities, despawn_waves},
+    timeline::WaveTimeline,
+};
+use crate::{
+    DespawnRestart, GameState, RESOLUTION_SCALE,
+    bullet::light::LightBullets,
+    campaign::{Campaign, StagePhase},
+    tween::TimeMult,
+};
+use bevy::{prelude::*, sprite::Anchor};
+use bevy_optix::pixel_perfect::HIGH_RES_LAYER;
+
+/// Seconds a looped entry gets to clear before it is spawned again.
+const LOOP_TIMEOUT: f32 = 12.;
+const SPEEDS: &[f32] = &[0.1, 0.25, 0.5, 1., 2., 4.];
+const NORMAL_SPEED: usize = 3;
+/// Entries listed on either side of the cursor.
+const LIST_RADIUS: usize = 6;
+
+pub struct TimelineDebugPlugin;
+
+impl Plugin for TimelineDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TimelineDebug>()
+            .add_systems(OnEnter(GameState::Restart), restart)
+            .add_systems(
+                Update,
+                (
+                    toggle_panel,
+                    (control_timeline, loop_entry, update_panel)
+                        .chain()
+                        .run_if(in_state(StagePhase::Playing)),
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Resource)]
+struct TimelineDebug {
+    cursor: usize,
+    looping: Option<usize>,
+    /// Index into [`SPEEDS`].
+    speed: usize,
+    paused: bool,
+}
+
+impl Default for TimelineDebug {
+    fn default() -> Self {
+        Self {
+            cursor: 0,
+            looping: None,
+            speed: NORMAL_SPEED,
+            paused: false,
+        }
+    }
+}
+
+fn restart(mut commands: Commands) {
+    commands.insert_resource(TimelineDebug::default());
+}
+
+#[derive(Component)]
+struct DebugPanel;
+
+fn toggle_panel(
+    mut commands: Commands,
+    server: Res<AssetServer>,
+    input: Res<ButtonInput<KeyCode>>,
+    panel: Option<Single<Entity, With<DebugPanel>>>,
+) {
+    if !input.just_pressed(KeyCode::F1) {
+        return;
+    }
+
+    match panel {
+        Some(panel) => commands.entity(*panel).despawn(),
+        None => {
+            commands.spawn((
+                DebugPanel,
+                DespawnRestart,
+                HIGH_RES_LAYER,
+                Text2d::default(),
+                TextFont {
+                    font_size: 12.,
+                    font: server.load("fonts/joystix.otf"),
+                    ..Default::default()
+                },
+                Transform::from_xyz(
+                    -crate::WIDTH / 2. * RESOLUTION_SCALE + 2. * RESOLUTION_SCALE,
+                    crate::HEIGHT / 2. * RESOLUTION_SCALE - 20. * RESOLUTION_SCALE,
+                    600.,
+                ),
+                Anchor::TopLeft,
+            ));
+        }
+    }
+}
+
+fn control_timeline(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    _panel: Single<&DebugPanel>,
+    mut debug: ResMut<TimelineDebug>,
+    mut mult: ResMut<TimeMult>,
+    campaign: Res<Campaign>,
+    stages: Res<Assets<Stage>>,
+    registry: Res<FormationRegistry>,
+    entities: WaveEntities,
+    mut light_bullets: ResMut<LightBullets>,
+) {
+    let Some(stage) = campaign.stage(&stages) else {
+        return;
+    };
+
+    if input.just_pressed(KeyCode::BracketLeft) {
+        debug.cursor = debug.cursor.saturating_sub(1);
+    }
+    if input.just_pressed(KeyCode::BracketRight) {
+        debug.cursor = (debug.cursor + 1).min(stage.waves.len().saturating_sub(1));
+    }
+
+    if input.just_pressed(KeyCode::F2) {
+        debug.looping = None;
+        despawn_waves(&mut commands, &entities);
+        light_bullets.clear();
+        commands.insert_resource(stage.timeline_from(&registry, debug.cursor));
+    }
+
+    if input.just_pressed(KeyCode::F3) {
+        despawn_waves(&mut commands, &entities);
+        light_bullets.clear();
+        match debug.looping {
+            // carry on with the rest of the stage
+            Some(index) if index == debug.cursor => {
+                debug.looping = None;
+                commands.insert_resource(stage.timeline_from(&registry, index + 1));
+            }
+            _ => {
+                debug.looping = Some(debug.cursor);
+                commands.insert_resource(stage.entry_timeline(
+                    &registry,
+                    debug.cursor,
+                    LOOP_TIMEOUT,
+                ));
+            }
+        }
+    }
+
+    let mut time_changed = false;
+    if input.just_pressed(KeyCode::F4) {
+        debug.paused = !debug.paused;
+        time_changed = true;
+    }
+    if input.just_pressed(KeyCode::Minus) {
+        debug.speed = debug.speed.saturating_sub(1);
+        time_changed = true;
+    }
+    if input.just_pressed(KeyCode::Equal) {
+        debug.speed = (debug.speed + 1).min(SPEEDS.len() - 1);
+        time_changed = true;
+    }
+    if time_changed {
+        mult.0 = if debug.paused {
+            0.
+        } else {
+            SPEEDS[debug.speed]
+        };
+    }
+}
+
+fn loop_entry(
+    mut commands: Commands,
+    debug: Res<TimelineDebug>,
+    timeline: Option<Res<WaveTimeline>>,
+    campaign: Res<Campaign>,
+    stages: Res<Assets<Stage>>,
+    registry: Res<FormationRegistry>,
+) {
+    let Some(index) = debug.looping else {
+        return;
+    };
+
+    if timeline.is_some_and(|timeline| !timeline.finished()) {
+        return;
+    }
+
+    if let Some(stage) = campaign.stage(&stages) {
+        commands.insert_resource(stage.entry_timeline(&registry, index, LOOP_TIMEOUT));
+    }
+}
+
+fn update_panel(
+    mut panel: Single<&mut Text2d, With<DebugPanel>>,
+    debug: Res<TimelineDebug>,
+    campaign: Res<Campaign>,
+    stages: Res<Assets<Stage>>,
+    timeline: Option<Res<WaveTimeline>>,
+    formations: Query<(Entity, Option<&Name>, &Units), With<FormationEntity>>,
+) {
+    let Some(stage) = campaign.stage(&stages) else {
+        return;
+    };
+
+    let current = timeline.and_then(|timeline| timeline.checkpoint());
+    let speed = if debug.paused {
+        "paused".to_string()
+    } else {
+        format!("x{:.2}", SPEEDS[debug.speed])
+    };
+
+    let mut lines = vec![format!("{}  {speed}", stage.name)];
+    let start = debug.cursor.saturating_sub(LIST_RADIUS);
+    for (index, entry) in stage
+        .waves
+        .iter()
+        .enumerate()
+        .skip(start)
+        .take(LIST_RADIUS * 2 + 1)
+    {
+        let cursor = if index == debug.cursor { '>' } else { ' ' };
+        let marker = if debug.looping == Some(index) {
+            '@'
+        } else if current == Some(index) {
+            '*'
+        } else {
+            ' '
+        };
+        lines.push(format!("{cursor}{marker}{index:>3} {entry}"));
+    }
+
+    lines.push(String::new());
+    lines.push("jumps keep power, points, and misses".into());
+    lines.push("and drop pending signals".into());
+
+    lines.push(String::new());
+    lines.push("formations".into());
+    for (entity, name, units) in formations.iter() {
+        let name = name.map(Name::as_str).unwrap_or("?");
+        lines.push(format!("  {name} {entity}: {}", units.len()));
+    }
+
+    panel.0 = lines.join("\n");
+}
@@ -1,6 +1,6 @@
 use super::InvincibleLaserNode;
 use super::OrbSlinger;
-use super::timeline::WaveTimeline;
+use super::timeline::{TimelineSignal, WaveTimeline};
 use crate::bullet::emitter::LaserEmitter;
 use crate::pickups::{Bomb, Pickup, PowerUp, Weapon};
 use crate::{Avian, DespawnRestart, GameState, boss::gradius};
@@ -25,15 +25,16 @@ pub struct FormationPlugin;
 
 impl Plugin for FormationPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            despawn_formations
-                .in_set(FormationSet)
-                .chain()
-                .run_if(in_state(GameState::Game)),
-        )
-        .add_systems(Avian, update_formations)
-        .add_tween_systems(apply_component_tween_system::<LaserEmitterTween>);
+        app.add_event::<TimelineSignal>()
+            .add_systems(
+                Update,
+                despawn_formations
+                    .in_set(FormationSet)
+                    .chain()
+                    .run_if(in_state(GameState::Game)),
+            )
+            .add_systems(Avian, update_formations)
+            .add_tween_systems(apply_component_tween_system::<LaserEmitterTween>);
     }
 }
 
@@ -196,6 +197,17 @@ fn on_remove_platoon(mut world: DeferredWorld, ctx: HookContext) {
 #[require(UnitDeaths)]
 pub struct Units(Vec<Entity>);
 
+impl Units {
+    /// Units left in the platoon.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
 #[derive(Default, Component)]
 struct UnitDeaths(Vec<Vec2>);
 
@@ -302,6 +314,16 @@ pub fn powerup(commands: &mut EntityCommands) {
     commands.insert(DropPowerup);
 }
 
+#[derive(Component)]
+struct ClearSignal(String);
+
+/// Write a [`TimelineSignal`] named `name` when the formation is cleared.
+pub fn signal(name: String) -> impl FnMut(&mut EntityCommands) + 'static {
+    move |commands| {
+        commands.insert(ClearSignal(name.clone()));
+    }
+}
+
 fn despawn_formations(
     mut commands: Commands,
     formations: Query<
@@ -311,15 +333,20 @@ fn despawn_formations(
             Option<&DropOption>,
             Option<&DropBomb>,
             Option<&DropPowerup>,
+            Option<&ClearSignal>,
         ),
         (With<FormationEntity>, Without<Units>),
     >,
+    mut signals: EventWriter<TimelineSignal>,
     //formations: Query<(Entity, &UnitDeaths), (With<Formation>, Without<Units>)>,
     //off_screen: Query<(Entity, &Transform, &Formation)>,
 ) {
     //let mut rng = rand::rng();
-    for (entity, deaths, option, bomb, powerup) in formations.iter() {
+    for (entity, deaths, option, bomb, powerup, signal) in formations.iter() {
         commands.entity(entity).despawn();
+        if let Some(signal) = signal {
+            signals.write(TimelineSignal(signal.0.clone()));
+        }
 
         let transform =
             Transform::from_translation(deaths.last_death_position().unwrap().extend(1.));
@@ -330,7 +357,7 @@ fn despawn_formations(
             commands.spawn((Bomb, transform));
         }
         if powerup.is_some() {
-            commands.spawn((PowerUp, transform));
+            commands.spawn((PowerUp::Large, transform));
         }
 
         //if rng.random_bool(0.75) {
@@ -1,35 +1,46 @@
 use super::Enemy;
 use super::LowHealthEffects;
+use super::archetype::EnemyArchetype;
+use super::brain::{Behaviour, BrainState, EnemyBrain, Exit};
 use super::formation::Formation;
 use super::formation::Platoon;
 use super::formation::animate_entrance;
 use crate::bullet::BulletTimer;
 use crate::bullet::Mine;
+use crate::bullet::emitter::BulletModifiers;
 use crate::bullet::emitter::EmitterBullet;
 use crate::bullet::emitter::EmitterDelay;
 use crate::bullet::emitter::EmitterSample;
 use crate::player::Player;
-use crate::{
-    bullet::emitter::BulletModifiers, effects::Explosion, health::Health, sprites::CellSprite,
-};
 use avian2d::prelude::*;
 use bevy::prelude::*;
 
 const BULLET_RATE: f32 = 2.;
 const BULLET_SPEED: f32 = 50.;
+const ENTRANCE_SECS: f32 = 1.5;
 
 #[derive(Default, Clone, Copy, Component)]
 #[require(
     Enemy,
-    Collider::rectangle(12., 12.),
-    CellSprite::new24("ships.png", UVec2::new(2, 1)),
-    Health::full(25.),
+    EnemyArchetype("mine_thrower"),
     LowHealthEffects,
-    MineEmitter,
-    Explosion::Big
+    EnemyBrain = MineThrower::brain()
 )]
 pub struct MineThrower;
 
+impl MineThrower {
+    /// Holds fire until it has moved into place.
+    fn brain() -> EnemyBrain {
+        EnemyBrain::new([
+            (
+                BrainState::Enter,
+                Behaviour::new().exit(Exit::After(ENTRANCE_SECS), BrainState::Attack),
+            ),
+            (BrainState::Attack, Behaviour::new().emitter(MineEmitter)),
+        ])
+    }
+}
+
 pub fn quad_mine_thrower() -> Formation {
     Formation::new(|formation: &mut EntityCommands, server: &AssetServer| {
         formation.with_children(|root| {
@@ -45,7 +56,7 @@ pub fn quad_mine_thrower() -> Formation {
                     Transform::from_xyz(-20., 0., 0.),
                 ),
                 None,
-                1.5,
+                ENTRANCE_SECS,
                 Vec3::ZERO,
                 Vec3::new(-20., 0., 0.),
                 Quat::default(),
@@ -61,7 +72,7 @@ pub fn quad_mine_thrower() -> Formation {
                     Transform::from_xyz(-20., 0., 0.),
                 ),
                 None,
-                1.5,
+                ENTRANCE_SECS,
                 Vec3::ZERO,
                 Vec3::new(20., 0., 0.),
                 Quat::default(),
@@ -77,7 +88,7 @@ pub fn quad_mine_thrower() -> Formation {
                     Transform::from_xyz(-20., 0., 0.),
                 ),
                 Some(1.),
-                1.5,
+                ENTRANCE_SECS,
                 Vec3::ZERO,
                 Vec3::new(40., 10., 0.),
                 Quat::default(),
@@ -93,7 +104,7 @@ pub fn quad_mine_thrower() -> Formation {
                     Transform::from_xyz(-20., 0., 0.),
                 ),
                 Some(1.),
-                1.5,
+                ENTRANCE_SECS,
                 Vec3::ZERO,
                 Vec3::new(-40., 10., 0.),
                 Quat::default(),
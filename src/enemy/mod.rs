@@ -1,4 +1,5 @@
 use self::{
+    archetype::EnemyArchetype,
     formation::{FormationPlugin, FormationSet},
     movement::*,
     timeline::LARGEST_SPRITE_SIZE,
@@ -9,12 +10,13 @@ use crate::{
     auto_collider::ImageCollider,
     bullet::{
         Destructable, Direction,
-        emitter::{EmitterAppExt, EmitterSystems, SpiralOrbEmitter},
+        emitter::{EmitterAppExt, EmitterSystems},
     },
-    effects::Explosion,
+    effects::{Explosion, hit::HitFeedback},
     health::{Dead, Health},
     pickups::PowerUp,
     player::Player,
+    rng::{GameRng, RngStream},
     sprites::{BehaviorRoot, CellSprite},
 };
 use avian2d::prelude::*;
@@ -27,35 +29,45 @@ use bevy::{
 use bevy_enoki::prelude::*;
 use bevy_optix::{debug::DebugRect, shake::TraumaCommands};
 use rand::{Rng, seq::IteratorRandom};
+use serde::Deserialize;
 use std::{f32::consts::PI, ops::Range};
 use strum::IntoEnumIterator;
 
+pub mod archetype;
 pub mod arcs;
+pub mod brain;
 pub mod buckshot;
 pub mod crisscross;
+#[cfg(debug_assertions)]
+mod debug;
 pub mod formation;
 pub mod minethrower;
 pub mod movement;
+pub mod path;
 pub mod scout;
+pub mod stage;
 pub mod swarm;
 pub mod timeline;
 pub mod verger;
 pub mod waller;
 
+/// Chance for an enemy without [`DropPowerUp`] to drop a small power item.
+const SMALL_POWER_CHANCE: f64 = 0.4;
+
 pub struct EnemyPlugin;
 
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<EnemyDeathEvent>()
-            .add_plugins((FormationPlugin, MovementPlugin))
-            .add_systems(OnEnter(GameState::Game), timeline::start_waves)
-            .add_systems(
-                PreUpdate,
-                (
-                    buckshot::track_player.before(EmitterSystems::Update),
-                    crisscross::swivel.after(EmitterSystems::Update),
-                ),
-            )
+            .add_plugins((
+                FormationPlugin,
+                MovementPlugin,
+                path::PathPlugin,
+                brain::BrainPlugin,
+                archetype::ArchetypePlugin,
+                stage::StagePlugin,
+            ))
+            .add_systems(PreUpdate, crisscross::swivel.after(EmitterSystems::Update))
             .add_systems(
                 Update,
                 (
@@ -70,15 +82,12 @@ impl Plugin for EnemyPlugin {
                 PostUpdate,
                 (handle_death, despawn_enemy).run_if(in_state(GameState::Game)),
             )
-            .add_emitter_system::<swarm::SwarmEmitter>()
-            .add_emitter_system::<verger::VergerEmitter>()
-            .add_emitter_system::<buckshot::BuckShotEmitter>()
-            .add_emitter_system::<waller::WallEmitter>()
             .add_emitter_system::<arcs::ArcsEmitter>()
             .add_emitter_system::<crisscross::CrisscrossEmitter>();
 
         #[cfg(debug_assertions)]
-        app.add_systems(First, timeline::timeline_skip.after(TimeSystem));
+        app.add_systems(First, timeline::timeline_skip.after(TimeSystem))
+            .add_plugins(debug::TimelineDebugPlugin);
     }
 }
 
@@ -93,20 +102,17 @@ impl Plugin for EnemyPlugin {
     CollisionLayers::new([Layer::Enemy], [Layer::Bullet, Layer::Player]),
     Destructable,
     Trauma,
+    Score,
+    HitFeedback = HitFeedback::ENEMY,
 )]
 pub struct Enemy;
 
 #[derive(Default, Component)]
 #[require(
     Enemy,
-    Health::full(20.),
-    Collider::circle(6.),
-    CellSprite::new24("ships.png", UVec2::new(0, 1)),
+    EnemyArchetype("orb_slinger"),
     LowHealthEffects,
     CollisionLayers::new([Layer::Enemy], [Layer::Bullet, Layer::Player]),
-    SpiralOrbEmitter,
-    Drops::splat(8),
-    Explosion::Big,
 )]
 #[component(on_add = Self::sprites)]
 pub struct OrbSlinger;
@@ -126,13 +132,10 @@ impl OrbSlinger {
 #[derive(Default, Component)]
 #[require(
     Enemy,
-    ImageCollider,
-    Health::full(8.),
+    EnemyArchetype("laser_node"),
     LowHealthEffects,
     DebugRect::from_size_color(Vec2::splat(8.), RED),
     CollisionLayers::new([Layer::Enemy], [Layer::Bullet, Layer::Player]),
-    Drops::splat(6),
-    Explosion::Small,
 )]
 pub struct LaserNode;
 
@@ -176,34 +179,23 @@ impl Default for Trauma {
     }
 }
 
-impl Trauma {
-    pub const NONE: Self = Trauma(0.);
+/// Points for destroying the enemy.
+#[derive(Component)]
+struct Score(usize);
+
+impl Default for Score {
+    fn default() -> Self {
+        Self(20)
+    }
 }
 
-#[derive(Component)]
+#[derive(Debug, Clone, Component, Deserialize)]
 struct Drops {
     parts: DropCount,
     shield: DropCount,
 }
 
-impl Drops {
-    pub fn new(parts: impl Into<DropCount>, shield: impl Into<DropCount>) -> Self {
-        Self {
-            parts: parts.into(),
-            shield: shield.into(),
-        }
-    }
-
-    pub fn splat(count: impl Into<DropCount>) -> Self {
-        let count = count.into();
-        Self {
-            parts: count.clone(),
-            shield: count,
-        }
-    }
-}
-
-#[derive(Clone)]
+#[derive(Debug, Clone, Deserialize)]
 enum DropCount {
     Static(usize),
     Range(Range<usize>),
@@ -220,24 +212,6 @@ impl DropCount {
     }
 }
 
-impl Into<DropCount> for usize {
-    fn into(self) -> DropCount {
-        DropCount::Static(self)
-    }
-}
-
-impl Into<DropCount> for Range<usize> {
-    fn into(self) -> DropCount {
-        DropCount::Range(self)
-    }
-}
-
-impl Into<DropCount> for f32 {
-    fn into(self) -> DropCount {
-        DropCount::One(self)
-    }
-}
-
 #[derive(Default, Component)]
 pub struct DropPowerUp;
 
@@ -246,6 +220,8 @@ pub struct EnemyDeathEvent {
     pub entity: Entity,
     pub position: Vec2,
     pub trauma: f32,
+    /// Points for destroying the enemy.
+    pub points: usize,
 }
 
 fn handle_death(
@@ -254,6 +230,7 @@ fn handle_death(
             Entity,
             &GlobalTransform,
             &Trauma,
+            &Score,
             Option<&Drops>,
             Option<&DropPowerUp>,
             Option<&Explosion>,
@@ -263,9 +240,10 @@ fn handle_death(
     mut commands: Commands,
     mut deaths: EventWriter<EnemyDeathEvent>,
     mut clusters: EventWriter<SpawnCluster>,
+    mut rng: ResMut<GameRng>,
 ) {
-    let mut rng = rand::rng();
-    for (entity, gt, trauma, drops, power_up, explosion) in q.iter() {
+    let rng = rng.stream(RngStream::Drops);
+    for (entity, gt, trauma, score, drops, power_up, explosion) in q.iter() {
         if explosion.is_some_and(|e| *e == Explosion::Big) {
             commands.entity(entity).despawn();
             //.despawn_related::<Children>()
@@ -306,18 +284,21 @@ fn handle_death(
             entity,
             position,
             trauma: trauma.0,
+            points: score.0,
         });
 
         if let Some(drops) = drops {
             clusters.write(SpawnCluster {
-                parts: drops.parts.count(&mut rng),
-                shield: drops.shield.count(&mut rng),
+                parts: drops.parts.count(rng),
+                shield: drops.shield.count(rng),
                 position,
             });
         }
 
         if power_up.is_some() {
-            commands.spawn((PowerUp, gt.compute_transform()));
+            commands.spawn((PowerUp::Large, gt.compute_transform()));
+        } else if rng.random_bool(SMALL_POWER_CHANCE) {
+            commands.spawn((PowerUp::Small, gt.compute_transform()));
         }
     }
 }
@@ -338,15 +319,16 @@ fn add_low_health_effects(
     mut commands: Commands,
     server: Res<AssetServer>,
     query: Query<(Entity, &Health), (With<LowHealthEffects>, Without<AppliedLowHealthEffects>)>,
+    mut rng: ResMut<GameRng>,
 ) {
     const DIST: f32 = 4.;
     const Y_OFFSET: f32 = 1.;
 
-    let mut rng = rand::rng();
+    let rng = rng.stream(RngStream::Enemies);
     for (entity, health) in query.iter() {
         if health.current() <= health.max() / 2.0 {
             let mut chosen = [Direction::default(); 3];
-            Direction::iter().choose_multiple_fill(&mut rng, &mut chosen);
+            Direction::iter().choose_multiple_fill(rng, &mut chosen);
             commands
                 .entity(entity)
                 .insert(AppliedLowHealthEffects)
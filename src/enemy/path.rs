@@ -0,0 +1,385 @@
+//! Movement along Catmull-Rom and Bezier curves at a constant speed.
+//!
+//! A [`PathShape`] is declared once, then built into a [`PathCurve`] that every unit of a platoon
+//! shares. Each unit follows it with its own [`Path`], delayed by its place in the platoon.
+//!
+//! ```ignore
+//! let curve = PathShape::catmull_rom(points)
+//!     .on(2, PathEvent::Pause(0.5))
+//!     .mirrored()
+//!     .build();
+//! let formation = path::platoon(curve, 4, SPEED, 0.25, |_| Swarm);
+//! ```
+use super::{
+    FaceVelocity,
+    formation::{Formation, Platoon},
+    timeline::ENEMY_Z,
+};
+use crate::{GameState, bullet::emitter::EmitterDelay};
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use std::{f32::consts::PI, sync::Arc};
+
+/// Samples taken between two control points.
+const SEGMENT_SAMPLES: usize = 16;
+
+pub struct PathPlugin;
+
+impl Plugin for PathPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, follow_paths.run_if(in_state(GameState::Game)));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Interpolation {
+    /// Passes through every point.
+    CatmullRom,
+    /// Cubic segments: an anchor, two handles, then the next anchor.
+    Bezier,
+}
+
+/// Points of a curve in the parent's space, with events along the way.
+#[derive(Clone)]
+pub struct PathShape {
+    interpolation: Interpolation,
+    points: Vec<Vec2>,
+    events: Vec<(usize, PathEvent)>,
+}
+
+impl PathShape {
+    /// A curve through every point.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are less than two points.
+    pub fn catmull_rom(points: impl IntoIterator<Item = Vec2>) -> Self {
+        let points = points.into_iter().collect::<Vec<_>>();
+        assert!(points.len() >= 2, "a path needs at least two points");
+        Self {
+            interpolation: Interpolation::CatmullRom,
+            points,
+            events: Vec::new(),
+        }
+    }
+
+    /// Cubic Bezier segments sharing their end anchors, so `[a, h1, h2, b, h3, h4, c]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the points do not form at least one whole segment.
+    pub fn bezier(points: impl IntoIterator<Item = Vec2>) -> Self {
+        let points = points.into_iter().collect::<Vec<_>>();
+        assert!(
+            points.len() >= 4 && (points.len() - 1) % 3 == 0,
+            "a bezier path needs 3n + 1 points"
+        );
+        Self {
+            interpolation: Interpolation::Bezier,
+            points,
+            events: Vec::new(),
+        }
+    }
+
+    /// Run `event` when a unit reaches `point`.
+    ///
+    /// Bezier paths only count their anchors, so `point` 1 is the end of the first segment.
+    pub fn on(mut self, point: usize, event: PathEvent) -> Self {
+        self.events.push((point, event));
+        self
+    }
+
+    /// Flip the path horizontally.
+    pub fn mirrored(mut self) -> Self {
+        for point in self.points.iter_mut() {
+            point.x = -point.x;
+        }
+        self
+    }
+
+    /// Rotate the path counter-clockwise around the parent's origin.
+    pub fn rotated(mut self, radians: f32) -> Self {
+        let rotation = Vec2::from_angle(radians);
+        for point in self.points.iter_mut() {
+            *point = rotation.rotate(*point);
+        }
+        self
+    }
+
+    pub fn translated(mut self, offset: Vec2) -> Self {
+        for point in self.points.iter_mut() {
+            *point += offset;
+        }
+        self
+    }
+
+    /// Sample the curve by distance travelled.
+    pub fn build(&self) -> PathCurve {
+        let samples = match self.interpolation {
+            Interpolation::CatmullRom => {
+                let last = self.points.len() - 1;
+                let mut samples = Vec::with_capacity(last * SEGMENT_SAMPLES + 1);
+                for i in 0..last {
+                    let p0 = self.points[i.saturating_sub(1)];
+                    let p1 = self.points[i];
+                    let p2 = self.points[i + 1];
+                    let p3 = self.points[(i + 2).min(last)];
+                    samples.extend(segment_times().map(|t| catmull_rom(p0, p1, p2, p3, t)));
+                }
+                samples.push(self.points[last]);
+                samples
+            }
+            Interpolation::Bezier => {
+                let mut samples = Vec::new();
+                for segment in self.points.windows(4).step_by(3) {
+                    samples.extend(segment_times().map(|t| bezier(segment, t)));
+                }
+                samples.push(self.points[self.points.len() - 1]);
+                samples
+            }
+        };
+
+        let mut lengths = Vec::with_capacity(samples.len());
+        let mut length = 0.;
+        lengths.push(length);
+        for pair in samples.windows(2) {
+            length += pair[0].distance(pair[1]);
+            lengths.push(length);
+        }
+
+        let mut events = self
+            .events
+            .iter()
+            .map(|(point, event)| {
+                let sample = (point * SEGMENT_SAMPLES).min(lengths.len() - 1);
+                (lengths[sample], event.clone())
+            })
+            .collect::<Vec<_>>();
+        events.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        PathCurve(Arc::new(Sampled {
+            samples,
+            lengths,
+            events,
+        }))
+    }
+}
+
+fn segment_times() -> impl Iterator<Item = f32> {
+    (0..SEGMENT_SAMPLES).map(|i| i as f32 / SEGMENT_SAMPLES as f32)
+}
+
+fn catmull_rom(p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2, t: f32) -> Vec2 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    0.5 * (2. * p1
+        + (p2 - p0) * t
+        + (2. * p0 - 5. * p1 + 4. * p2 - p3) * t2
+        + (3. * p1 - p0 - 3. * p2 + p3) * t3)
+}
+
+fn bezier(points: &[Vec2], t: f32) -> Vec2 {
+    let u = 1. - t;
+    points[0] * u * u * u
+        + points[1] * 3. * u * u * t
+        + points[2] * 3. * u * t * t
+        + points[3] * t * t * t
+}
+
+/// Something a unit does when it reaches a point on its path.
+#[derive(Clone)]
+pub enum PathEvent {
+    /// Stop for some seconds.
+    Pause(f32),
+    /// Start shooting, cutting any [`EmitterDelay`] short.
+    Fire,
+    /// Stop shooting for some seconds.
+    HoldFire(f32),
+    /// Anything else, like swapping out the unit's emitter.
+    Command(Arc<dyn Fn(&mut EntityCommands) + Send + Sync>),
+}
+
+impl PathEvent {
+    pub fn command(command: impl Fn(&mut EntityCommands) + Send + Sync + 'static) -> Self {
+        Self::Command(Arc::new(command))
+    }
+}
+
+/// A [`PathShape`] sampled by distance. Cheap to clone.
+#[derive(Clone)]
+pub struct PathCurve(Arc<Sampled>);
+
+struct Sampled {
+    samples: Vec<Vec2>,
+    /// Distance from the start to each sample.
+    lengths: Vec<f32>,
+    events: Vec<(f32, PathEvent)>,
+}
+
+impl PathCurve {
+    pub fn length(&self) -> f32 {
+        self.0.lengths[self.0.lengths.len() - 1]
+    }
+
+    pub fn position(&self, distance: f32) -> Vec2 {
+        let (i, t) = self.segment(distance);
+        self.0.samples[i].lerp(self.0.samples[i + 1], t)
+    }
+
+    /// Direction of travel at `distance`.
+    pub fn direction(&self, distance: f32) -> Vec2 {
+        let (i, _) = self.segment(distance);
+        (self.0.samples[i + 1] - self.0.samples[i]).normalize_or_zero()
+    }
+
+    /// The sample `distance` is after, and how far along it is towards the next one.
+    fn segment(&self, distance: f32) -> (usize, f32) {
+        let lengths = &self.0.lengths;
+        let i = lengths
+            .partition_point(|length| *length <= distance)
+            .clamp(1, lengths.len() - 1)
+            - 1;
+        let span = lengths[i + 1] - lengths[i];
+        let t = if span > 0. {
+            ((distance - lengths[i]) / span).clamp(0., 1.)
+        } else {
+            0.
+        };
+        (i, t)
+    }
+}
+
+/// Moves the entity along a [`PathCurve`], replacing its translation.
+///
+/// Once the path ends, the entity keeps going in a straight line with a [`LinearVelocity`], unless
+/// it was told to [stop](Path::stopping).
+#[derive(Clone, Component)]
+pub struct Path {
+    curve: PathCurve,
+    speed: f32,
+    delay: f32,
+    travelled: f32,
+    pause: f32,
+    next_event: usize,
+    stop: bool,
+}
+
+impl Path {
+    pub fn new(curve: PathCurve, speed: f32) -> Self {
+        Self {
+            curve,
+            speed,
+            delay: 0.,
+            travelled: 0.,
+            pause: 0.,
+            next_event: 0,
+            stop: false,
+        }
+    }
+
+    /// Wait at the start of the path for some seconds.
+    pub fn delayed(mut self, secs: f32) -> Self {
+        self.delay = secs;
+        self
+    }
+
+    /// Follow `spacing` seconds behind the previous unit of a platoon.
+    pub fn spaced(self, index: usize, spacing: f32) -> Self {
+        self.delayed(index as f32 * spacing)
+    }
+
+    /// Come to a halt at the end of the path.
+    pub fn stopping(mut self) -> Self {
+        self.stop = true;
+        self
+    }
+
+    pub fn travelled(&self) -> f32 {
+        self.travelled
+    }
+}
+
+/// A formation of `count` units following `curve` one after the other, `spacing` seconds apart.
+///
+/// The units are placed at the start of the path, so `unit` should not include a [`Transform`].
+pub fn platoon<B: Bundle>(
+    curve: PathCurve,
+    count: usize,
+    speed: f32,
+    spacing: f32,
+    unit: impl Fn(usize) -> B + Send + Sync + 'static,
+) -> Formation {
+    Formation::with_velocity(Vec2::ZERO, move |formation: &mut EntityCommands, _| {
+        let start = curve.position(0.).extend(ENEMY_Z);
+        formation.with_children(|root| {
+            for i in 0..count {
+                root.spawn((
+                    unit(i),
+                    Platoon(root.target_entity()),
+                    Path::new(curve.clone(), speed).spaced(i, spacing),
+                    Transform::from_translation(start),
+                ));
+            }
+        });
+    })
+}
+
+fn follow_paths(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut paths: Query<(Entity, &mut Path, &mut Transform, Has<FaceVelocity>)>,
+) {
+    let delta = time.delta_secs();
+
+    for (entity, mut path, mut transform, face_velocity) in paths.iter_mut() {
+        if path.delay > 0. {
+            path.delay -= delta;
+        } else if path.pause > 0. {
+            path.pause -= delta;
+        } else {
+            path.travelled += path.speed * delta;
+        }
+
+        let curve = path.curve.clone();
+        while let Some((distance, event)) = curve.0.events.get(path.next_event) {
+            if *distance > path.travelled {
+                break;
+            }
+            path.next_event += 1;
+
+            match event {
+                PathEvent::Pause(secs) => {
+                    path.travelled = *distance;
+                    path.pause = *secs;
+                }
+                PathEvent::Fire => {
+                    commands.entity(entity).remove::<EmitterDelay>();
+                }
+                PathEvent::HoldFire(secs) => {
+                    commands.entity(entity).insert(EmitterDelay::new(*secs));
+                }
+                PathEvent::Command(command) => command(&mut commands.entity(entity)),
+            }
+        }
+
+        let position = curve.position(path.travelled);
+        transform.translation = position.extend(transform.translation.z);
+
+        let direction = curve.direction(path.travelled);
+        if face_velocity && direction != Vec2::ZERO {
+            transform.rotation = Quat::from_rotation_z(direction.to_angle() + PI / 2.);
+        }
+
+        if path.travelled >= curve.length() {
+            let velocity = if path.stop {
+                Vec2::ZERO
+            } else {
+                direction * path.speed
+            };
+            commands
+                .entity(entity)
+                .remove::<Path>()
+                .insert(LinearVelocity(velocity));
+        }
+    }
+}
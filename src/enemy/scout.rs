@@ -1,15 +1,11 @@
 use super::Enemy;
-use super::Trauma;
+use super::archetype::EnemyArchetype;
 use super::formation::Formation;
 use super::formation::Platoon;
 use crate::bullet::emitter::EmitterDelay;
 use crate::bullet::emitter::ShotLimit;
 use crate::enemy::FaceVelocity;
-use crate::enemy::swarm::SwarmEmitter;
 use crate::tween::DespawnTweenFinish;
-use crate::{
-    auto_collider::ImageCollider, effects::Explosion, health::Health, sprites::CellSprite,
-};
 use bevy::prelude::*;
 use bevy_tween::combinator::sequence;
 use bevy_tween::combinator::tween;
@@ -21,12 +17,7 @@ use std::time::Duration;
 #[derive(Default, Component)]
 #[require(
     Enemy,
-    ImageCollider,
-    Health::full(1.),
-    CellSprite::new8("shooters/SpaceShooterAssetPack_Ships.png", UVec2::new(4, 1)),
-    SwarmEmitter,
-    Trauma(0.04),
-    Explosion::Small,
+    EnemyArchetype("scout"),
     DespawnTweenFinish,
     FaceVelocity,
     ShotLimit(1)
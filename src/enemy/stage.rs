@@ -0,0 +1,435 @@
+//! Stage files describe a [`WaveTimeline`] in RON, along with the stage's presentation.
+//!
+//! The wave list is made of [`StageStep`]s. A `Spawn` names a formation from the
+//! [`FormationRegistry`], with optional parameters and drop modifiers. The other steps wait,
+//! branch, or run tracks in parallel. The boss is spawned after the last step:
+//!
+//! ```ron
+//! #![enable(unwrap_variant_newtypes)]
+//! (
+//!     name: "Outer Rim",
+//!     boss: "boss",
+//!     delay: 1.5,
+//!     waves: [
+//!         Spawn(formation: "swarm_swing", mirror: true, delay: 1.0),
+//!         Spawn(formation: "crisscross", modifiers: [PowerUp, Signal("cross")]),
+//!         WaitFor(signal: "cross", timeout: Some(6.0)),
+//!         Branch(
+//!             condition: Power(2.0),
+//!             then: [Spawn(formation: "verger")],
+//!             otherwise: [Spawn(formation: "swarm_three")],
+//!         ),
+//!         WaitClear(timeout: Some(10.0)),
+//!     ],
+//! )
+//! ```
+//!
+//! Saving the stage file while playing restarts the stage from the first wave.
+use super::{
+    Enemy,
+    archetype::{ArchetypeTable, EnemyArchetypes},
+    arcs, buckshot, crisscross,
+    formation::{self, Formation, FormationEntity},
+    minethrower, scout, swarm,
+    timeline::{Condition, Step, WaveTimeline},
+    verger, waller,
+};
+use crate::{
+    assets::RonAssetAppExt,
+    bullet::{Bullet, PlayerBullet},
+    campaign::{Campaign, StagePhase},
+    music::StageMusic,
+    pickups::Weapon,
+};
+use bevy::{platform::collections::HashMap, prelude::*};
+use serde::Deserialize;
+use std::{fmt, ops::Range};
+
+pub struct StagePlugin;
+
+impl Plugin for StagePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_ron_asset::<Stage>(&["stage.ron"])
+            .insert_resource(FormationRegistry::builtin())
+            .add_systems(OnEnter(StagePhase::Playing), queue_stage)
+            .add_systems(
+                Update,
+                (restart_modified_stage, start_stage)
+                    .chain()
+                    .before(super::timeline::update_waves)
+                    .run_if(in_state(StagePhase::Playing)),
+            );
+    }
+}
+
+#[derive(Debug, Clone, Asset, TypePath, Deserialize)]
+pub struct Stage {
+    /// Shown on the stage's intro card.
+    pub name: String,
+    /// Image behind the scrolling clouds.
+    #[serde(default = "default_background")]
+    pub background: String,
+    #[serde(default)]
+    pub music: StageMusic,
+    /// Name in the [`FormationRegistry`], spawned once the waves run out.
+    pub boss: String,
+    /// Seconds before the first wave.
+    #[serde(default)]
+    pub delay: f32,
+    /// Fast forward this many seconds into the stage, only in debug builds.
+    #[serde(default)]
+    pub skip: Option<f32>,
+    pub waves: Vec<StageStep>,
+}
+
+/// One entry in a stage's wave list. See [`Step`].
+#[derive(Debug, Clone, Deserialize)]
+pub enum StageStep {
+    /// Spawn a formation, then wait for the wave's delay.
+    Spawn(Wave),
+    Wait(f32),
+    WaitClear {
+        #[serde(default)]
+        timeout: Option<f32>,
+    },
+    WaitFor {
+        signal: String,
+        #[serde(default)]
+        timeout: Option<f32>,
+    },
+    Branch {
+        condition: Condition,
+        then: Vec<StageStep>,
+        #[serde(default)]
+        otherwise: Vec<StageStep>,
+    },
+    Parallel(Vec<Vec<StageStep>>),
+}
+
+impl StageStep {
+    /// Push the timeline steps this entry expands to. Waves with an unknown formation are skipped.
+    fn build(&self, registry: &FormationRegistry, steps: &mut Vec<Step>) {
+        match self {
+            Self::Spawn(wave) => {
+                let Some(formation) = wave.formation(registry) else {
+                    return;
+                };
+                steps.push(Step::Spawn(formation));
+                if wave.delay > 0. {
+                    steps.push(Step::Wait(wave.delay));
+                }
+            }
+            Self::Wait(secs) => steps.push(Step::Wait(*secs)),
+            Self::WaitClear { timeout } => steps.push(Step::WaitClear { timeout: *timeout }),
+            Self::WaitFor { signal, timeout } => steps.push(Step::WaitFor {
+                signal: signal.clone(),
+                timeout: *timeout,
+            }),
+            Self::Branch {
+                condition,
+                then,
+                otherwise,
+            } => steps.push(Step::Branch {
+                condition: *condition,
+                then: build_steps(then, registry),
+                otherwise: build_steps(otherwise, registry),
+            }),
+            Self::Parallel(tracks) => steps.push(Step::Parallel(
+                tracks
+                    .iter()
+                    .map(|track| build_steps(track, registry))
+                    .collect(),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for StageStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Spawn(wave) if wave.mirror => write!(f, "{} (mirrored)", wave.formation),
+            Self::Spawn(wave) => write!(f, "{}", wave.formation),
+            Self::Wait(secs) => write!(f, "wait {secs:.1}s"),
+            Self::WaitClear { .. } => write!(f, "wait for clear"),
+            Self::WaitFor { signal, .. } => write!(f, "wait for `{signal}`"),
+            Self::Branch { condition, .. } => write!(f, "branch on {condition:?}"),
+            Self::Parallel(tracks) => write!(f, "{} parallel tracks", tracks.len()),
+        }
+    }
+}
+
+fn build_steps(entries: &[StageStep], registry: &FormationRegistry) -> Vec<Step> {
+    let mut steps = Vec::new();
+    for entry in entries.iter() {
+        entry.build(registry, &mut steps);
+    }
+    steps
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Wave {
+    /// Name in the [`FormationRegistry`].
+    pub formation: String,
+    #[serde(default)]
+    pub position: Option<Vec2>,
+    /// Flip the formation horizontally.
+    #[serde(default)]
+    pub mirror: bool,
+    #[serde(default)]
+    pub modifiers: Vec<Modifier>,
+    /// Seconds until the next step.
+    #[serde(default)]
+    pub delay: f32,
+}
+
+fn default_background() -> String {
+    "space.png".into()
+}
+
+impl Wave {
+    fn formation(&self, registry: &FormationRegistry) -> Option<Formation> {
+        let constructor = lookup(registry, &self.formation)?;
+        let name = Name::new(self.formation.clone());
+        Some(
+            self.modifiers
+                .iter()
+                .fold(constructor(&self.params()), |formation, modifier| {
+                    modifier.apply(formation)
+                })
+                .with(move |formation| {
+                    formation.insert(name.clone());
+                }),
+        )
+    }
+
+    fn params(&self) -> WaveParams {
+        WaveParams {
+            position: self.position,
+            mirror: self.mirror,
+        }
+    }
+}
+
+/// Applied once the whole formation is destroyed.
+#[derive(Debug, Clone, Deserialize)]
+pub enum Modifier {
+    PowerUp,
+    Bomb,
+    Option(Weapon),
+    /// Write a [`TimelineSignal`](super::timeline::TimelineSignal) with this name.
+    Signal(String),
+}
+
+impl Modifier {
+    fn apply(&self, formation: Formation) -> Formation {
+        match self {
+            Self::PowerUp => formation.with(formation::powerup),
+            Self::Bomb => formation.with(formation::bomb),
+            Self::Option(weapon) => formation.with(formation::option(*weapon)),
+            Self::Signal(name) => formation.with(formation::signal(name.clone())),
+        }
+    }
+}
+
+/// Parameters a formation constructor can read from its [`Wave`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WaveParams {
+    pub position: Option<Vec2>,
+    pub mirror: bool,
+}
+
+impl WaveParams {
+    /// The wave's position, or `default`, mirrored if the wave is.
+    pub fn position(&self, default: Vec2) -> Vec2 {
+        let position = self.position.unwrap_or(default);
+        if self.mirror {
+            position.with_x(-position.x)
+        } else {
+            position
+        }
+    }
+}
+
+pub type FormationConstructor = fn(&WaveParams) -> Formation;
+
+/// Maps the formation names used in stage files to their constructors.
+#[derive(Default, Resource)]
+pub struct FormationRegistry(HashMap<&'static str, FormationConstructor>);
+
+impl FormationRegistry {
+    pub fn register(&mut self, name: &'static str, constructor: FormationConstructor) -> &mut Self {
+        if self.0.insert(name, constructor).is_some() {
+            warn!("formation `{name}` was registered twice");
+        }
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<FormationConstructor> {
+        self.0.get(name).copied()
+    }
+
+    fn builtin() -> Self {
+        let mut registry = Self::default();
+        registry
+            .register("swarm_three", |_| swarm::three())
+            .register("swarm_swing", |params| swarm::swing(params.mirror))
+            .register("buckshot", |params| {
+                if params.mirror {
+                    buckshot::left()
+                } else {
+                    buckshot::right()
+                }
+            })
+            .register("buckshot_double", |_| buckshot::double())
+            .register("scout_triple", |params| {
+                scout::triple(params.position(Vec2::new(0., -45.)))
+            })
+            .register("crisscross", |params| {
+                crisscross::single(params.position(Vec2::new(-20., -40.)))
+            })
+            .register("verger", |params| {
+                verger::verger(params.position(Vec2::new(0., -32.)))
+            })
+            .register("mine_thrower", |_| minethrower::quad_mine_thrower())
+            .register("waller_double", |_| waller::double())
+            .register("arcs", |_| arcs::persistent())
+            .register("orb_slinger", |_| formation::orb_slinger())
+            .register("double_orb_slinger", |_| formation::double_orb_slinger())
+            .register("laser_maze", |_| formation::laser_maze())
+            .register("laser_ladder", |_| formation::laser_ladder())
+            .register("boss", |_| formation::boss());
+        registry
+    }
+}
+
+impl Stage {
+    /// Build the stage's timeline.
+    pub fn timeline(&self, registry: &FormationRegistry) -> WaveTimeline {
+        let mut steps = vec![Step::Wait(self.delay)];
+        steps.extend(self.steps(registry, 0..self.waves.len()));
+        steps.extend(self.boss(registry).map(Step::Spawn));
+
+        let timeline = WaveTimeline::from_steps(steps);
+        match self.skip {
+            Some(secs) if cfg!(debug_assertions) => timeline.skip(secs),
+            _ => timeline,
+        }
+    }
+
+    /// The stage's timeline from entry `start` onward, without the opening delay.
+    pub fn timeline_from(&self, registry: &FormationRegistry, start: usize) -> WaveTimeline {
+        let start = start.min(self.waves.len());
+        WaveTimeline::from_steps(
+            self.steps(registry, start..self.waves.len())
+                .into_iter()
+                .chain(self.boss(registry).map(Step::Spawn)),
+        )
+    }
+
+    /// Only entry `index`, held until everything it spawned is cleared or `timeout` passes.
+    pub fn entry_timeline(
+        &self,
+        registry: &FormationRegistry,
+        index: usize,
+        timeout: f32,
+    ) -> WaveTimeline {
+        let end = (index + 1).min(self.waves.len());
+        let steps = self.steps(registry, index.min(end)..end);
+        WaveTimeline::from_steps(steps.into_iter().chain([Step::WaitClear {
+            timeout: Some(timeout),
+        }]))
+    }
+
+    /// Steps for the entries in `range`, each preceded by its [`Step::Checkpoint`].
+    fn steps(&self, registry: &FormationRegistry, range: Range<usize>) -> Vec<Step> {
+        let mut steps = Vec::new();
+        for (index, entry) in self.waves[range.clone()].iter().enumerate() {
+            steps.push(Step::Checkpoint(range.start + index));
+            entry.build(registry, &mut steps);
+        }
+        steps
+    }
+
+    fn boss(&self, registry: &FormationRegistry) -> Option<Formation> {
+        lookup(registry, &self.boss).map(|constructor| constructor(&WaveParams::default()))
+    }
+}
+
+fn lookup(registry: &FormationRegistry, name: &str) -> Option<FormationConstructor> {
+    let constructor = registry.get(name);
+    if constructor.is_none() {
+        error!("unknown formation `{name}`, skipping");
+    }
+    constructor
+}
+
+/// The stage starts as soon as it and the enemy archetypes are loaded.
+#[derive(Resource)]
+struct PendingStage;
+
+fn queue_stage(mut commands: Commands) {
+    commands.insert_resource(PendingStage);
+}
+
+fn start_stage(
+    mut commands: Commands,
+    pending: Option<Res<PendingStage>>,
+    campaign: Res<Campaign>,
+    stages: Res<Assets<Stage>>,
+    registry: Res<FormationRegistry>,
+    archetypes: Res<EnemyArchetypes>,
+    tables: Res<Assets<ArchetypeTable>>,
+) {
+    // enemies need their stats as soon as they spawn
+    if pending.is_none() || !archetypes.is_loaded(&tables) {
+        return;
+    }
+
+    if let Some(stage) = campaign.stage(&stages) {
+        if crate::SKIP_WAVES {
+            commands.insert_resource(WaveTimeline::from_steps(
+                stage.boss(&registry).map(Step::Spawn),
+            ));
+        } else {
+            commands.insert_resource(stage.timeline(&registry));
+        }
+        commands.remove_resource::<PendingStage>();
+    }
+}
+
+fn restart_modified_stage(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<Stage>>,
+    campaign: Res<Campaign>,
+    entities: WaveEntities,
+) {
+    if !events
+        .read()
+        .any(|event| event.is_modified(campaign.handle().id()))
+    {
+        return;
+    }
+
+    info!("stage modified, restarting");
+    despawn_waves(&mut commands, &entities);
+    commands.remove_resource::<WaveTimeline>();
+    commands.insert_resource(PendingStage);
+}
+
+/// Formations, enemies, and enemy bullets: everything a restarted stage clears away.
+pub type WaveEntities<'w, 's> = Query<
+    'w,
+    's,
+    Entity,
+    Or<(
+        With<FormationEntity>,
+        With<Enemy>,
+        (With<Bullet>, Without<PlayerBullet>),
+    )>,
+>;
+
+pub fn despawn_waves(commands: &mut Commands, entities: &WaveEntities) {
+    for entity in entities.iter() {
+        commands.entity(entity).try_despawn();
+    }
+}
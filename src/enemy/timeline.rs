@@ -1,89 +1,129 @@
-use super::{buckshot, crisscross, formation::*, minethrower, scout, swarm, verger, waller};
-use crate::player::Player;
+//! A [`WaveTimeline`] runs a list of [`Step`]s in order.
+//!
+//! Besides spawning formations on a timer, steps can hold the timeline until the formations on
+//! screen are cleared or a [`TimelineSignal`] arrives, branch on how well the player is doing, or
+//! run several tracks in parallel.
+use super::formation::*;
+use crate::{
+    campaign::StageTally,
+    player::{Player, Power},
+    points::Points,
+};
 use avian2d::prelude::*;
 use bevy::prelude::*;
-use std::time::Duration;
+use serde::Deserialize;
+use std::{collections::VecDeque, time::Duration};
 
 pub const LARGEST_SPRITE_SIZE: f32 = 16.;
 pub const ENEMY_Z: f32 = 0.;
 
-#[cfg(not(debug_assertions))]
-const START_DELAY: f32 = 1.5;
-#[cfg(debug_assertions)]
-const START_DELAY: f32 = 0.;
-
-pub fn start_waves(mut commands: Commands) {
-    if crate::SKIP_WAVES {
-        commands.insert_resource(WaveTimeline::new([(boss(), 0.)]));
-    } else {
-        commands.insert_resource(WaveTimeline::new_delayed(
-            START_DELAY,
-            [
-                //(arcs::persistent(), 999.),
-                //
-                (swarm::three(), 3.),
-                (swarm::right_swing(), 1.),
-                (swarm::left_swing(), 1.),
-                (buckshot::double(), 1.),
-                (scout::triple(Vec2::new(0., -45.)), 3.),
-                (swarm::right_swing(), 1.),
-                (swarm::left_swing(), 0.5),
-                //
-                (crisscross::single(Vec2::new(-20., -40.)).with(powerup), 3.),
-                //
-                (verger::verger(Vec2::new(-35., -32.)), 4.),
-                (buckshot::right(), 2.),
-                (scout::triple(Vec2::new(0., -45.)), 1.),
-                (swarm::right_swing(), 0.2),
-                (swarm::right_swing(), 1.),
-                (buckshot::right(), 1.),
-                (swarm::left_swing(), 0.2),
-                (swarm::left_swing(), 1.),
-                //
-                (crisscross::single(Vec2::new(-30., -40.)).with(powerup), 4.),
-                //
-                (minethrower::quad_mine_thrower(), 3.),
-                (buckshot::right(), 1.),
-                (minethrower::quad_mine_thrower().with(bomb), 2.),
-                (buckshot::left(), 1.),
-                (waller::double(), 2.),
-                (verger::verger(Vec2::new(0., -32.)), 1.),
-                (swarm::left_swing(), 0.2),
-                (swarm::left_swing(), 1.),
-                (swarm::right_swing(), 0.2),
-                (swarm::right_swing(), 1.),
-                (verger::verger(Vec2::new(-35., -32.)), 1.),
-                (verger::verger(Vec2::new(35., -32.)), 1.),
-                (swarm::left_swing(), 0.2),
-                (swarm::left_swing(), 1.),
-                (swarm::right_swing(), 0.2),
-                (swarm::right_swing(), 10.),
-                //
-                (boss(), 0.),
-            ],
-        ));
+/// One step of a [`WaveTimeline`].
+pub enum Step {
+    /// Spawn a formation and move straight on to the next step.
+    Spawn(Formation),
+    /// Wait for this many seconds.
+    ///
+    /// Time a wait overshoots by carries into the next one, so a run of waits never drifts.
+    Wait(f32),
+    /// Wait until every formation this track has spawned is cleared, or until the timeout.
+    ///
+    /// Formations without units, like lasers, never hold this up.
+    WaitClear { timeout: Option<f32> },
+    /// Wait until a [`TimelineSignal`] with this name is written, or until the timeout.
+    ///
+    /// Signals written before the step is reached are kept until a step waits on them.
+    WaitFor {
+        signal: String,
+        timeout: Option<f32>,
+    },
+    /// Continue with `then` if the condition holds when the step is reached, otherwise with
+    /// `otherwise`.
+    Branch {
+        condition: Condition,
+        then: Vec<Step>,
+        otherwise: Vec<Step>,
+    },
+    /// Run every track side by side. Moves on once all of them are done.
+    Parallel(Vec<Vec<Step>>),
+    /// Record how far the timeline has come, see [`WaveTimeline::checkpoint`].
+    Checkpoint(usize),
+}
+
+impl Step {
+    /// Formations this step could spawn, counting both arms of a branch.
+    fn spawns(&self) -> usize {
+        match self {
+            Self::Spawn(_) => 1,
+            Self::Branch {
+                then, otherwise, ..
+            } => then.iter().chain(otherwise).map(Self::spawns).sum(),
+            Self::Parallel(tracks) => tracks.iter().flatten().map(Self::spawns).sum(),
+            _ => 0,
+        }
     }
 }
 
+/// Checked by [`Step::Branch`].
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub enum Condition {
+    /// The player has at least this much [`Power`].
+    Power(f32),
+    /// The player has at least this many [`Points`].
+    Points(usize),
+    /// The player has not been hit this stage.
+    NoMiss,
+}
+
+impl Condition {
+    fn holds(&self, progress: &Progress) -> bool {
+        match *self {
+            Self::Power(power) => progress.power >= power,
+            Self::Points(points) => progress.points >= points,
+            Self::NoMiss => progress.misses == 0,
+        }
+    }
+}
+
+/// How the player is doing, for [`Condition`]s.
+struct Progress {
+    power: f32,
+    points: usize,
+    misses: usize,
+}
+
+/// Resumes a [`Step::WaitFor`] waiting on the same name.
+#[derive(Debug, Clone, Event)]
+pub struct TimelineSignal(pub String);
+
 #[derive(Resource)]
 pub struct WaveTimeline {
-    seq: Vec<(Formation, f32)>,
-    timer: Timer,
-    index: usize,
+    track: Track,
+    /// Signals received but not yet waited on.
+    signals: Vec<String>,
+    checkpoint: Option<usize>,
+    spawned: usize,
+    total: usize,
     finished: bool,
     skip: Option<Timer>,
 }
 
 impl WaveTimeline {
+    /// Spawn each formation, then wait for its delay.
     pub fn new(seq: impl IntoIterator<Item = (Formation, f32)>) -> Self {
-        Self::new_delayed(0., seq)
+        Self::from_steps(
+            seq.into_iter()
+                .flat_map(|(formation, delay)| [Step::Spawn(formation), Step::Wait(delay)]),
+        )
     }
 
-    pub fn new_delayed(delay: f32, seq: impl IntoIterator<Item = (Formation, f32)>) -> Self {
+    pub fn from_steps(steps: impl IntoIterator<Item = Step>) -> Self {
+        let track = Track::new(steps);
         Self {
-            seq: seq.into_iter().collect(),
-            timer: Timer::from_seconds(delay, TimerMode::Repeating),
-            index: 0,
+            total: track.steps.iter().map(Step::spawns).sum(),
+            track,
+            signals: Vec::new(),
+            checkpoint: None,
+            spawned: 0,
             finished: false,
             skip: None,
         }
@@ -98,33 +138,183 @@ impl WaveTimeline {
         self.skip.is_some()
     }
 
-    pub fn tick(&mut self, time: &Time) {
-        self.timer.tick(time.delta());
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    /// The last [`Step::Checkpoint`] passed.
+    pub fn checkpoint(&self) -> Option<usize> {
+        self.checkpoint
+    }
+
+    /// The number of waves spawned so far.
+    pub fn wave(&self) -> usize {
+        self.spawned
     }
 
-    pub fn next(&mut self) -> Option<&mut Formation> {
-        if self.timer.just_finished() {
-            match self.seq.get_mut(self.index) {
-                Some((formation, duration)) => {
-                    self.timer.set_duration(Duration::from_secs_f32(*duration));
-                    self.index += 1;
-                    Some(formation)
+    /// The number of waves in the timeline, counting both arms of every branch.
+    pub fn waves(&self) -> usize {
+        self.total
+    }
+}
+
+/// A sequence of steps, run one after the other.
+struct Track {
+    steps: VecDeque<Step>,
+    wait: Option<Wait>,
+    /// Spawned by this track, for [`Step::WaitClear`].
+    formations: Vec<Entity>,
+    /// How far the last [`Wait::Timer`] ran past its end this update.
+    overshoot: Duration,
+}
+
+enum Wait {
+    /// Time left.
+    Timer(Duration),
+    Clear(Option<Timer>),
+    Signal(String, Option<Timer>),
+    Tracks(Vec<Track>),
+}
+
+/// What a [`Track`] needs from the world for one update.
+struct TrackContext<'a> {
+    delta: Duration,
+    progress: Progress,
+    signals: &'a mut Vec<String>,
+    alive: &'a dyn Fn(Entity) -> bool,
+    spawn: &'a mut dyn FnMut(Formation) -> Entity,
+    checkpoint: Option<usize>,
+    spawned: usize,
+}
+
+impl Track {
+    fn new(steps: impl IntoIterator<Item = Step>) -> Self {
+        Self {
+            steps: steps.into_iter().collect(),
+            wait: None,
+            formations: Vec::new(),
+            overshoot: Duration::ZERO,
+        }
+    }
+
+    /// Run steps until one blocks. Returns `true` once the track is done.
+    fn update(&mut self, ctx: &mut TrackContext) -> bool {
+        if let Some(wait) = self.wait.as_mut() {
+            if !wait.update(ctx, &self.formations, &mut self.overshoot) {
+                return false;
+            }
+
+            if let Some(Wait::Tracks(tracks)) = self.wait.take() {
+                self.formations
+                    .extend(tracks.into_iter().flat_map(|track| track.formations));
+            }
+        }
+
+        while let Some(step) = self.steps.pop_front() {
+            self.wait = match step {
+                Step::Spawn(formation) => {
+                    self.formations.push((ctx.spawn)(formation));
+                    ctx.spawned += 1;
+                    None
                 }
-                None => {
-                    self.finished = true;
+                Step::Checkpoint(checkpoint) => {
+                    ctx.checkpoint = Some(checkpoint);
+                    None
+                }
+                Step::Branch {
+                    condition,
+                    then,
+                    otherwise,
+                } => {
+                    let branch = if condition.holds(&ctx.progress) {
+                        then
+                    } else {
+                        otherwise
+                    };
+                    for step in branch.into_iter().rev() {
+                        self.steps.push_front(step);
+                    }
                     None
                 }
+                Step::Wait(secs) => {
+                    let left = Duration::from_secs_f32(secs);
+                    if left <= self.overshoot {
+                        self.overshoot -= left;
+                        continue;
+                    }
+                    Some(Wait::Timer(left - std::mem::take(&mut self.overshoot)))
+                }
+                Step::WaitClear { timeout } => Some(Wait::Clear(timeout.map(timer))),
+                Step::WaitFor { signal, timeout } => Some(Wait::Signal(signal, timeout.map(timer))),
+                Step::Parallel(tracks) => {
+                    Some(Wait::Tracks(tracks.into_iter().map(Track::new).collect()))
+                }
+            };
+
+            // waits are first checked on the next update, once the formations spawned above
+            // actually exist
+            if self.wait.is_some() {
+                self.overshoot = Duration::ZERO;
+                return false;
             }
-        } else {
-            None
         }
+
+        true
     }
+}
 
-    pub fn finished(&self) -> bool {
-        self.finished
+impl Wait {
+    fn update(
+        &mut self,
+        ctx: &mut TrackContext,
+        formations: &[Entity],
+        overshoot: &mut Duration,
+    ) -> bool {
+        match self {
+            Self::Timer(left) => match ctx.delta.checked_sub(*left) {
+                Some(over) => {
+                    *overshoot = over;
+                    true
+                }
+                None => {
+                    *left -= ctx.delta;
+                    false
+                }
+            },
+            Self::Clear(timeout) => {
+                !formations.iter().any(|entity| (ctx.alive)(*entity))
+                    || timed_out(timeout, ctx.delta)
+            }
+            Self::Signal(name, timeout) => {
+                match ctx.signals.iter().position(|signal| signal == name) {
+                    Some(index) => {
+                        ctx.signals.swap_remove(index);
+                        true
+                    }
+                    None => timed_out(timeout, ctx.delta),
+                }
+            }
+            Self::Tracks(tracks) => {
+                let mut done = true;
+                for track in tracks.iter_mut() {
+                    done = track.update(ctx) && done;
+                }
+                done
+            }
+        }
     }
 }
 
+fn timer(secs: f32) -> Timer {
+    Timer::from_seconds(secs, TimerMode::Once)
+}
+
+fn timed_out(timeout: &mut Option<Timer>, delta: Duration) -> bool {
+    timeout
+        .as_mut()
+        .is_some_and(|timeout| timeout.tick(delta).finished())
+}
+
 #[cfg(debug_assertions)]
 pub fn timeline_skip(
     mut commands: Commands,
@@ -157,17 +347,28 @@ pub fn update_waves(
     server: Res<AssetServer>,
     controller: Option<ResMut<WaveTimeline>>,
     time: Res<Time>,
+    mut signals: EventReader<TimelineSignal>,
+    formations: Query<(), (With<FormationEntity>, With<Units>)>,
+    power: Res<Power>,
+    points: Res<Points>,
+    tally: Res<StageTally>,
 ) {
     let Some(mut controller) = controller else {
+        signals.clear();
         return;
     };
 
     if controller.finished() {
+        signals.clear();
         return;
     }
 
-    controller.tick(&time);
-    if let Some(formation) = controller.next() {
+    let controller = &mut *controller;
+    controller
+        .signals
+        .extend(signals.read().map(|signal| signal.0.clone()));
+
+    let mut spawn = |mut formation: Formation| {
         let mut commands = commands.spawn((
             FormationEntity(formation.velocity),
             Transform::from_translation(Vec3::new(0., crate::HEIGHT / 2., ENEMY_Z)),
@@ -176,5 +377,179 @@ pub fn update_waves(
         for modifier in formation.modifiers.iter_mut() {
             modifier(&mut commands);
         }
+        commands.id()
+    };
+    let alive = |entity: Entity| formations.contains(entity);
+
+    let mut ctx = TrackContext {
+        delta: time.delta(),
+        progress: Progress {
+            power: power.get(),
+            points: points.get(),
+            misses: tally.misses(),
+        },
+        signals: &mut controller.signals,
+        alive: &alive,
+        spawn: &mut spawn,
+        checkpoint: None,
+        spawned: 0,
+    };
+    controller.finished = controller.track.update(&mut ctx);
+    controller.spawned += ctx.spawned;
+    if ctx.checkpoint.is_some() {
+        controller.checkpoint = ctx.checkpoint;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TICK: Duration = Duration::from_millis(250);
+
+    /// Runs a [`Track`] a quarter second at a time, with formations alive until `cleared`.
+    struct Runner {
+        track: Track,
+        spawned: Vec<Entity>,
+        cleared: bool,
+        power: f32,
+        misses: usize,
+        signals: Vec<String>,
+    }
+
+    impl Runner {
+        fn new(steps: impl IntoIterator<Item = Step>) -> Self {
+            Self {
+                track: Track::new(steps),
+                spawned: Vec::new(),
+                cleared: false,
+                power: 0.,
+                misses: 0,
+                signals: Vec::new(),
+            }
+        }
+
+        fn update(&mut self) -> bool {
+            let cleared = self.cleared;
+            let spawned = &mut self.spawned;
+            let alive = move |_: Entity| !cleared;
+            let mut spawn = |_: Formation| {
+                let entity = Entity::from_raw(spawned.len() as u32);
+                spawned.push(entity);
+                entity
+            };
+
+            let mut ctx = TrackContext {
+                delta: TICK,
+                progress: Progress {
+                    power: self.power,
+                    points: 0,
+                    misses: self.misses,
+                },
+                signals: &mut self.signals,
+                alive: &alive,
+                spawn: &mut spawn,
+                checkpoint: None,
+                spawned: 0,
+            };
+            self.track.update(&mut ctx)
+        }
+
+        /// Updates it took to finish the track, at most `max`.
+        fn run(&mut self, max: usize) -> Option<usize> {
+            (1..=max).find(|_| self.update())
+        }
+    }
+
+    fn spawn() -> Step {
+        Step::Spawn(Formation::new(|_: &mut EntityCommands, _: &AssetServer| {}))
+    }
+
+    #[test]
+    fn wait_clear_holds_until_formations_die() {
+        let mut runner = Runner::new([spawn(), Step::WaitClear { timeout: None }, spawn()]);
+        assert_eq!(runner.run(4), None);
+        assert_eq!(runner.spawned.len(), 1);
+
+        runner.cleared = true;
+        assert!(runner.update());
+        assert_eq!(runner.spawned.len(), 2);
+
+        let mut runner = Runner::new([spawn(), Step::WaitClear { timeout: Some(0.5) }]);
+        assert_eq!(runner.run(4), Some(3));
+    }
+
+    #[test]
+    fn branch_takes_the_arm_its_condition_picks() {
+        let branch = || Step::Branch {
+            condition: Condition::Power(2.),
+            then: vec![spawn(), spawn()],
+            otherwise: vec![spawn()],
+        };
+
+        let mut runner = Runner::new([branch()]);
+        runner.power = 1.;
+        assert!(runner.update());
+        assert_eq!(runner.spawned.len(), 1);
+
+        let mut runner = Runner::new([branch()]);
+        runner.power = 2.;
+        assert!(runner.update());
+        assert_eq!(runner.spawned.len(), 2);
+    }
+
+    #[test]
+    fn no_miss_branch_is_lost_after_a_hit() {
+        let branch = || Step::Branch {
+            condition: Condition::NoMiss,
+            then: vec![spawn(), spawn()],
+            otherwise: vec![spawn()],
+        };
+
+        let mut runner = Runner::new([branch()]);
+        assert!(runner.update());
+        assert_eq!(runner.spawned.len(), 2);
+
+        let mut runner = Runner::new([branch()]);
+        runner.misses = 1;
+        assert!(runner.update());
+        assert_eq!(runner.spawned.len(), 1);
+    }
+
+    #[test]
+    fn parallel_waits_for_every_track() {
+        let mut runner = Runner::new([
+            Step::Parallel(vec![
+                vec![spawn(), Step::Wait(0.25)],
+                vec![Step::Wait(0.75), spawn()],
+            ]),
+            spawn(),
+        ]);
+
+        assert_eq!(runner.run(4), None);
+        assert_eq!(runner.spawned.len(), 1);
+        assert!(runner.update());
+        assert_eq!(runner.spawned.len(), 3);
+    }
+
+    #[test]
+    fn waits_carry_their_overshoot() {
+        let mut runner = Runner::new([Step::Wait(0.375), Step::Wait(0.125), spawn()]);
+        assert_eq!(runner.run(4), Some(3));
+    }
+
+    #[test]
+    fn signals_are_kept_until_waited_on() {
+        let mut runner = Runner::new([
+            Step::Wait(0.25),
+            Step::WaitFor {
+                signal: "go".into(),
+                timeout: None,
+            },
+        ]);
+        runner.signals.push("go".into());
+
+        assert_eq!(runner.run(3), Some(3));
+        assert!(runner.signals.is_empty());
     }
 }
@@ -1,38 +1,64 @@
 use super::Enemy;
 use super::LowHealthEffects;
+use super::archetype::EnemyArchetype;
+use super::brain::{Behaviour, BrainState, EnemyBrain, Exit, Movement};
 use super::formation::Formation;
 use super::formation::Platoon;
 use super::formation::animate_entrance;
-use crate::boss::gradius::emitters::SpiralOffset;
-use crate::bullet::RedOrb;
-use crate::bullet::emitter::BulletCommands;
-use crate::bullet::emitter::BulletSpeed;
-use crate::bullet::emitter::Emitter;
-use crate::bullet::emitter::EmitterBullet;
-use crate::bullet::emitter::EmitterCtx;
-use crate::bullet::emitter::ORB_SPEED;
-use crate::bullet::emitter::PulseTime;
-use crate::bullet::emitter::PulseTimer;
-use crate::bullet::emitter::Rate;
-use crate::bullet::emitter::ShootEmitter;
-use crate::{bullet::emitter::BulletModifiers, effects::Explosion, health::Health};
+use super::path::PathShape;
+use crate::bullet::pattern::PatternEmitter;
 use avian2d::prelude::*;
 use bevy::color::palettes::css::YELLOW;
 use bevy::prelude::*;
 use bevy_optix::debug::DebugCircle;
 
+pub const PATTERN: &str = "patterns/verger.pattern.ron";
+const ENTRANCE_SECS: f32 = 1.5;
+const SHOTS_PER_VOLLEY: usize = 20;
+/// Offset of the sway between volleys, away from the screen's edge.
+const SWAY: Vec2 = Vec2::new(16., -8.);
+const SWAY_SPEED: f32 = 24.;
+
 #[derive(Default, Clone, Copy, Component)]
 #[require(
     Enemy,
-    Collider::circle(21.),
-    Health::full(100.),
+    EnemyArchetype("verger"),
     LowHealthEffects,
-    VergerEmitter,
-    Explosion::Big,
     DebugCircle::color(21., YELLOW)
 )]
 pub struct Verger;
 
+/// Fires a few bursts, sways aside, and does it again.
+fn brain(position: Vec2) -> EnemyBrain {
+    let side = if position.x > 0. { 1. } else { -1. };
+    let sway = PathShape::catmull_rom([
+        position,
+        position + Vec2::new(-side * SWAY.x, SWAY.y),
+        position,
+    ])
+    .build();
+
+    EnemyBrain::new([
+        (
+            BrainState::Enter,
+            Behaviour::new().exit(Exit::After(ENTRANCE_SECS), BrainState::Attack),
+        ),
+        (
+            BrainState::Attack,
+            Behaviour::new()
+                .movement(Movement::Hold)
+                .emitter(PatternEmitter::new(PATTERN))
+                .exit(Exit::Shots(SHOTS_PER_VOLLEY), BrainState::Reposition),
+        ),
+        (
+            BrainState::Reposition,
+            Behaviour::new()
+                .movement(Movement::Path(sway, SWAY_SPEED))
+                .exit(Exit::Arrived, BrainState::Attack),
+        ),
+    ])
+}
+
 pub fn verger(position: Vec2) -> Formation {
     Formation::with_velocity(
         Vec2::ZERO,
@@ -41,9 +67,9 @@ pub fn verger(position: Vec2) -> Formation {
             animate_entrance(
                 server,
                 &mut formation.commands(),
-                (Verger, ChildOf(id), Platoon(id)),
+                (Verger, ChildOf(id), Platoon(id), brain(position)),
                 None,
-                1.5,
+                ENTRANCE_SECS,
                 Vec3::new(0., 16., 0.),
                 position.extend(0.),
                 Quat::default(),
@@ -52,38 +78,3 @@ pub fn verger(position: Vec2) -> Formation {
         },
     )
 }
-
-#[derive(Default, Clone, Copy, Component)]
-#[require(Transform, Emitter, SpiralOffset, BulletSpeed::new(ORB_SPEED))]
-pub struct VergerEmitter;
-
-impl ShootEmitter for VergerEmitter {
-    type Timer = PulseTimer;
-
-    fn timer(&self, _mods: &BulletModifiers) -> Self::Timer {
-        PulseTimer::new(Rate::Secs(2.), 3.0, 0.1, 10)
-    }
-
-    fn spawn_bullets(
-        &self,
-        mut commands: BulletCommands,
-        transform: Transform,
-        ctx: EmitterCtx<Self::Timer>,
-    ) {
-        let bullets = 6;
-        let mut angle_offset = ctx.timer.current_pulse() as f32 * std::f32::consts::TAU
-            / (bullets * ctx.timer.pulses()) as f32;
-        if ctx.timer.current_pulse() % 2 == 0 {
-            angle_offset *= -1.;
-        }
-
-        for angle in 0..bullets {
-            let angle = (angle as f32 / bullets as f32) * std::f32::consts::TAU + angle_offset;
-            commands.spawn_angled(angle, (RedOrb, transform));
-        }
-    }
-
-    fn sample() -> Option<EmitterBullet> {
-        Some(EmitterBullet::Orb)
-    }
-}
@@ -0,0 +1,275 @@
+//! Headless simulation for integration tests.
+//!
+//! [`TestGame`] runs [`GamePlugins`] under [`HeadlessPlugins`]: no window, GPU, or audio device.
+//! Every update advances time by exactly one fixed timestep, so a test can reason in ticks.
+//!
+//! ```no_run
+//! use cucumber::harness::TestGame;
+//!
+//! let mut game = TestGame::with_seed(7);
+//! game.enter_game().ticks(600);
+//! assert!(game.timeline().is_some_and(|timeline| timeline.wave() > 0));
+//! ```
+use crate::{
+    GamePlugins, GameState,
+    boss::capture::CaptureHistory,
+    campaign::{Campaign, StagePhase},
+    enemy::timeline::WaveTimeline,
+    health::{Health, Shield},
+    lives::Lives,
+    player::{AliveContext, Player, Power},
+    points::Points,
+    replay::ReplayFrame,
+    rng::GameRng,
+    ship::{ShipDefinition, Ships},
+    stats::Stats,
+};
+use bevy::{
+    app::PluginGroupBuilder, prelude::*, state::app::StatesPlugin, time::TimeUpdateStrategy,
+};
+use bevy_enhanced_input::prelude::*;
+
+/// Ticks [`TestGame::enter_game`] waits for before giving up.
+const ENTER_GAME_TICKS: usize = 600;
+
+/// The engine plugins [`GamePlugins`] needs, minus everything that touches a window, the GPU, or
+/// an audio device.
+///
+/// Assets that would normally be registered by rendering and audio plugins are registered here so
+/// that the game can still load and hold handles to them.
+pub struct HeadlessPlugins;
+
+impl PluginGroup for HeadlessPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add_group(MinimalPlugins)
+            .add(TransformPlugin)
+            .add(StatesPlugin)
+            .add(bevy::input::InputPlugin)
+            .add(AssetPlugin::default())
+            .add(ImagePlugin::default_nearest())
+            .add(HeadlessAssetPlugin)
+    }
+}
+
+struct HeadlessAssetPlugin;
+
+impl Plugin for HeadlessAssetPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Mesh>()
+            .init_asset::<ColorMaterial>()
+            .init_asset::<TextureAtlasLayout>()
+            .init_asset::<bevy_seedling::sample::Sample>()
+            .init_asset::<bevy_enoki::prelude::Particle2dEffect>()
+            .init_asset::<bevy_enoki::prelude::SpriteParticle2dMaterial>();
+    }
+}
+
+/// A headless game, stepped one fixed tick at a time.
+pub struct TestGame {
+    app: App,
+    ship: usize,
+}
+
+impl Default for TestGame {
+    fn default() -> Self {
+        Self::with_seed(0)
+    }
+}
+
+impl TestGame {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a game whose randomness is derived from `seed`.
+    pub fn with_seed(seed: u64) -> Self {
+        let mut app = App::new();
+        app.add_plugins((HeadlessPlugins, GamePlugins))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(
+                Time::<Fixed>::default().timestep(),
+            ))
+            .init_resource::<HeldInput>()
+            .add_systems(First, mock_held_input);
+
+        app.finish();
+        app.cleanup();
+        app.world_mut().resource_mut::<GameRng>().pin(seed);
+        // keep test runs out of the player's history
+        app.insert_resource(CaptureHistory::in_memory());
+
+        Self { app, ship: 0 }
+    }
+
+    pub fn app(&mut self) -> &mut App {
+        &mut self.app
+    }
+
+    pub fn world(&self) -> &World {
+        self.app.world()
+    }
+
+    /// Advance the game by one fixed tick.
+    pub fn tick(&mut self) -> &mut Self {
+        self.app.update();
+        self
+    }
+
+    pub fn ticks(&mut self, ticks: usize) -> &mut Self {
+        for _ in 0..ticks {
+            self.app.update();
+        }
+        self
+    }
+
+    /// Tick until `condition` holds, for at most `max_ticks`.
+    ///
+    /// Returns the number of ticks it took, or `None` if the condition never held.
+    pub fn run_until(
+        &mut self,
+        max_ticks: usize,
+        mut condition: impl FnMut(&World) -> bool,
+    ) -> Option<usize> {
+        for tick in 0..max_ticks {
+            if condition(self.app.world()) {
+                return Some(tick);
+            }
+            self.app.update();
+        }
+        condition(self.app.world()).then_some(max_ticks)
+    }
+
+    /// Skip the opening and ship select, then tick until the game reaches [`GameState::Game`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the game does not start within a few seconds of game time.
+    pub fn enter_game(&mut self) -> &mut Self {
+        // run `Startup` first, otherwise it would override the state set below
+        self.app.update();
+        self.app
+            .world_mut()
+            .resource_mut::<Ships>()
+            .select(self.ship);
+        self.run_until(ENTER_GAME_TICKS, |world| {
+            world
+                .resource::<Ships>()
+                .selected(world.resource::<Assets<ShipDefinition>>())
+                .is_some()
+        })
+        .expect("ship did not load");
+
+        if self.state() != GameState::Game {
+            self.app
+                .world_mut()
+                .resource_mut::<NextState<GameState>>()
+                .set(GameState::StartGame);
+        }
+
+        self.run_until(ENTER_GAME_TICKS, |world| {
+            *world.resource::<State<GameState>>().get() == GameState::Game
+        })
+        .expect("game did not start");
+        self
+    }
+
+    /// Pick the ship the player spawns as. Call before [`TestGame::enter_game`].
+    pub fn select_ship(&mut self, index: usize) -> &mut Self {
+        self.ship = index;
+        self
+    }
+
+    pub fn state(&self) -> GameState {
+        *self.app.world().resource::<State<GameState>>().get()
+    }
+
+    /// The current stage's phase, while in [`GameState::Game`].
+    pub fn phase(&self) -> Option<StagePhase> {
+        self.app
+            .world()
+            .get_resource::<State<StagePhase>>()
+            .map(|phase| *phase.get())
+    }
+
+    /// Index of the stage being played in the [`Campaign`].
+    pub fn stage(&self) -> usize {
+        self.app.world().resource::<Campaign>().current()
+    }
+
+    /// Hold the player's actions in `frame` until they are changed again.
+    pub fn hold(&mut self, frame: ReplayFrame) -> &mut Self {
+        self.app.world_mut().resource_mut::<HeldInput>().0 = frame;
+        self
+    }
+
+    /// Release every action held with [`TestGame::hold`].
+    pub fn release(&mut self) -> &mut Self {
+        self.hold(ReplayFrame::default())
+    }
+
+    pub fn points(&self) -> usize {
+        self.app.world().resource::<Points>().get()
+    }
+
+    pub fn lives(&self) -> usize {
+        self.app.world().resource::<Lives>().get()
+    }
+
+    pub fn power(&self) -> f32 {
+        self.app.world().resource::<Power>().get()
+    }
+
+    pub fn stats(&self) -> &Stats {
+        self.app.world().resource::<Stats>()
+    }
+
+    /// The player entity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the player is not alive.
+    pub fn player(&mut self) -> Entity {
+        self.app
+            .world_mut()
+            .query_filtered::<Entity, With<Player>>()
+            .single(self.app.world())
+            .expect("player is alive")
+    }
+
+    /// The player's health, if the player is alive.
+    pub fn player_health(&mut self) -> Option<Health> {
+        self.app
+            .world_mut()
+            .query_filtered::<&Health, With<Player>>()
+            .single(self.app.world())
+            .ok()
+            .copied()
+    }
+
+    /// The player's shield, if the player is alive.
+    pub fn player_shield(&mut self) -> Option<Shield> {
+        self.app
+            .world_mut()
+            .query_filtered::<&Shield, With<Player>>()
+            .single(self.app.world())
+            .ok()
+            .copied()
+    }
+
+    /// The current stage's timeline, once the waves have started.
+    pub fn timeline(&self) -> Option<&WaveTimeline> {
+        self.app.world().get_resource::<WaveTimeline>()
+    }
+}
+
+#[derive(Default, Resource)]
+struct HeldInput(ReplayFrame);
+
+fn mock_held_input(
+    input: Res<HeldInput>,
+    player: Option<Single<&mut Actions<AliveContext>, With<Player>>>,
+) {
+    if let Some(mut actions) = player {
+        input.0.mock(&mut actions);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::Avian;
 use avian2d::prelude::*;
 use bevy::prelude::*;
+use serde::Deserialize;
 use std::ops::Deref;
 
 pub struct HealthPlugin;
@@ -11,10 +12,18 @@ pub struct HealthSet;
 impl Plugin for HealthPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<DamageEvent>()
+            .add_event::<DamageDealt>()
+            .add_event::<ShieldBroken>()
             .configure_sets(Avian, HealthSet.after(PhysicsSet::Sync))
             .add_systems(
                 Avian,
-                (handle_damage, insert_dead, despawn_dead)
+                (
+                    handle_damage,
+                    regen_shields,
+                    remove_no_shield,
+                    insert_dead,
+                    despawn_dead,
+                )
                     .chain()
                     .in_set(HealthSet),
             );
@@ -95,6 +104,7 @@ impl Shield {
 
     pub fn heal(&mut self, heal: f32) {
         self.current = (self.current + heal).min(self.max);
+        self.empty = self.current == 0.0;
     }
 
     pub fn damage(&mut self, damage: f32) {
@@ -119,6 +129,10 @@ impl Shield {
         self.empty || self.current == 0.0
     }
 
+    pub fn is_full(&self) -> bool {
+        self.current == self.max
+    }
+
     /// Calculate the current proportion of health
     /// relative to full.
     pub fn proportion(&self) -> f32 {
@@ -127,9 +141,35 @@ impl Shield {
 }
 
 /// Entity's [`Shield`] has reached 0.
+///
+/// Removed once the shield is charged again.
 #[derive(Default, Component)]
 pub struct NoShield;
 
+/// Recharges an entity's [`Shield`] once it has gone `delay` seconds without taking damage.
+#[derive(Debug, Clone, Copy, Component)]
+#[require(Shield::full(0.))]
+pub struct ShieldRegen {
+    pub delay: f32,
+    /// Shield restored per second.
+    pub rate: f32,
+    since_damage: f32,
+}
+
+impl ShieldRegen {
+    pub const fn new(delay: f32, rate: f32) -> Self {
+        Self {
+            delay,
+            rate,
+            since_damage: 0.,
+        }
+    }
+
+    pub fn recharging(&self) -> bool {
+        self.since_damage >= self.delay
+    }
+}
+
 /// Entity's [`Health`] has reached 0.
 #[derive(Default, Component)]
 pub struct Dead;
@@ -138,10 +178,109 @@ pub struct Dead;
 #[derive(Default, Component)]
 pub struct DespawnDead;
 
+/// How damage is dealt, for [`Resistances`] and [`Armour`].
+///
+/// Bullets deal the kind on their entity, [`Kinetic`](Self::Kinetic) if they have none.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Component, Deserialize)]
+pub enum DamageKind {
+    #[default]
+    Kinetic,
+    Explosive,
+    /// Lasers. Ignores [`Armour`].
+    Energy,
+}
+
 #[derive(Debug, Clone, Copy, Event)]
 pub struct DamageEvent {
     pub entity: Entity,
     pub damage: f32,
+    pub kind: DamageKind,
+    /// Whatever dealt the damage, if it is known. Bullets are the source of their own damage,
+    /// except [light bullets](crate::bullet::light), which are not entities.
+    pub source: Option<Entity>,
+}
+
+impl DamageEvent {
+    pub fn new(entity: Entity, damage: f32) -> Self {
+        Self {
+            entity,
+            damage,
+            kind: DamageKind::default(),
+            source: None,
+        }
+    }
+
+    pub fn kind(mut self, kind: DamageKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn source(mut self, source: Entity) -> Self {
+        self.source = Some(source);
+        self
+    }
+}
+
+/// Fraction of each [`DamageKind`] an entity ignores. `1.` is immune.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Component, Deserialize)]
+#[serde(default)]
+pub struct Resistances {
+    pub kinetic: f32,
+    pub explosive: f32,
+    pub energy: f32,
+}
+
+impl Resistances {
+    pub fn get(&self, kind: DamageKind) -> f32 {
+        match kind {
+            DamageKind::Kinetic => self.kinetic,
+            DamageKind::Explosive => self.explosive,
+            DamageKind::Energy => self.energy,
+        }
+    }
+}
+
+/// Flat reduction to every hit, after [`Resistances`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Component, Deserialize)]
+pub struct Armour(pub f32);
+
+/// Mitigate `damage` of `kind`.
+pub fn mitigate(
+    damage: f32,
+    kind: DamageKind,
+    resistances: Option<&Resistances>,
+    armour: Option<&Armour>,
+) -> f32 {
+    let resisted = resistances.map_or(0., |r| r.get(kind).clamp(0., 1.));
+    let mut damage = damage * (1. - resisted);
+    if let Some(armour) = armour {
+        if kind != DamageKind::Energy {
+            damage -= armour.0;
+        }
+    }
+    damage.max(0.)
+}
+
+/// Written when a hit empties an entity's [`Shield`], alongside its [`DamageDealt`].
+#[derive(Debug, Clone, Copy, Event)]
+pub struct ShieldBroken {
+    pub entity: Entity,
+    pub source: Option<Entity>,
+}
+
+/// Written for every [`DamageEvent`] that reached its target.
+#[derive(Debug, Clone, Copy, Event)]
+pub struct DamageDealt {
+    pub entity: Entity,
+    pub source: Option<Entity>,
+    pub kind: DamageKind,
+    /// Damage after [`Resistances`] and [`Armour`], taken by the shield and health together.
+    pub amount: f32,
+    /// Damage beyond what was left of the entity's health.
+    pub overkill: f32,
+    /// The entity's [`Shield`] was emptied by this hit.
+    pub shield_broke: bool,
+    pub killed: bool,
 }
 
 #[derive(Debug, Default, Clone, Copy, Component)]
@@ -166,24 +305,93 @@ impl Damage {
 }
 
 pub fn handle_damage(
-    mut healths: Query<(Option<&mut Shield>, &mut Health), Without<Invincible>>,
+    mut commands: Commands,
+    mut healths: Query<
+        (
+            Option<&mut Shield>,
+            &mut Health,
+            Option<&Resistances>,
+            Option<&Armour>,
+        ),
+        Without<Invincible>,
+    >,
     mut reader: EventReader<DamageEvent>,
+    mut writer: EventWriter<DamageDealt>,
+    mut broken: EventWriter<ShieldBroken>,
 ) {
     for event in reader.read() {
-        if let Ok((shield, mut health)) = healths.get_mut(event.entity) {
-            if let Some(mut shield) = shield {
-                if shield.current() < event.damage {
-                    let remaining = shield.current();
-                    if !shield.empty() {
-                        shield.damage_all();
-                    }
-                    health.damage(event.damage - remaining);
-                } else {
-                    shield.damage(event.damage);
+        let Ok((shield, mut health, resistances, armour)) = healths.get_mut(event.entity) else {
+            continue;
+        };
+        let damage = mitigate(event.damage, event.kind, resistances, armour);
+        let alive = !health.dead();
+
+        let mut shield_broke = false;
+        let mut to_health = damage;
+        if let Some(mut shield) = shield {
+            let was_empty = shield.empty();
+            if shield.current() < damage {
+                let remaining = shield.current();
+                if !was_empty {
+                    shield.damage_all();
                 }
+                to_health = damage - remaining;
             } else {
-                health.damage(event.damage);
+                shield.damage(damage);
+                to_health = 0.;
             }
+            shield_broke = !was_empty && shield.empty();
+        }
+
+        let overkill = (to_health - health.current()).max(0.);
+        if to_health > 0. {
+            health.damage(to_health);
+        }
+
+        if shield_broke {
+            commands.entity(event.entity).insert(NoShield);
+            broken.write(ShieldBroken {
+                entity: event.entity,
+                source: event.source,
+            });
+        }
+
+        writer.write(DamageDealt {
+            entity: event.entity,
+            source: event.source,
+            kind: event.kind,
+            amount: damage,
+            overkill,
+            shield_broke,
+            killed: alive && health.dead(),
+        });
+    }
+}
+
+pub fn regen_shields(
+    time: Res<Time>,
+    mut shields: Query<(Entity, &mut Shield, &mut ShieldRegen)>,
+    mut reader: EventReader<DamageDealt>,
+) {
+    let hit = reader.read().map(|dealt| dealt.entity).collect::<Vec<_>>();
+
+    for (entity, mut shield, mut regen) in shields.iter_mut() {
+        if hit.contains(&entity) {
+            regen.since_damage = 0.;
+            continue;
+        }
+
+        regen.since_damage += time.delta_secs();
+        if regen.recharging() && !shield.is_full() {
+            shield.heal(regen.rate * time.delta_secs());
+        }
+    }
+}
+
+pub fn remove_no_shield(mut commands: Commands, shields: Query<(Entity, &Shield), With<NoShield>>) {
+    for (entity, shield) in shields.iter() {
+        if !shield.empty() {
+            commands.entity(entity).remove::<NoShield>();
         }
     }
 }
@@ -0,0 +1,166 @@
+use crate::{
+    GameState,
+    bomb::Bombs,
+    player::{ActiveShot, Player, ShotKind},
+};
+use avian2d::prelude::*;
+use bevy::{
+    color::palettes::css::{RED, WHITE},
+    ecs::{component::HookContext, world::DeferredWorld},
+    prelude::*,
+};
+use serde::Deserialize;
+
+/// Charge gained per grazed bullet.
+const GRAZE_CHARGE: f32 = 1.;
+/// A full meter awards a bomb.
+const GRAZE_METER: f32 = 120.;
+
+const HITBOX_Z: f32 = 10.;
+
+pub struct HitboxPlugin;
+
+impl Plugin for HitboxPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<GrazeEvent>()
+            .init_resource::<GrazeMeter>()
+            .add_systems(OnEnter(GameState::StartGame), reset_meter)
+            .add_systems(Update, (show_hitbox, charge_meter));
+    }
+}
+
+/// The player's true hitbox and the radius a bullet has to pass within to graze.
+///
+/// Inserting a [`Hitbox`] replaces the entity's [`Collider`].
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Component)]
+#[component(on_insert = Self::on_insert)]
+pub struct Hitbox {
+    pub shape: HitboxShape,
+    pub graze_radius: f32,
+}
+
+impl Default for Hitbox {
+    fn default() -> Self {
+        Self {
+            shape: HitboxShape::Rectangle(Vec2::splat(2.)),
+            graze_radius: 15.,
+        }
+    }
+}
+
+impl Hitbox {
+    fn on_insert(mut world: DeferredWorld, ctx: HookContext) {
+        let hitbox = *world.get::<Hitbox>(ctx.entity).unwrap();
+        world
+            .commands()
+            .entity(ctx.entity)
+            .insert(hitbox.shape.collider());
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum HitboxShape {
+    Circle(f32),
+    Rectangle(Vec2),
+}
+
+impl HitboxShape {
+    pub fn collider(&self) -> Collider {
+        match *self {
+            Self::Circle(radius) => Collider::circle(radius),
+            Self::Rectangle(size) => Collider::rectangle(size.x, size.y),
+        }
+    }
+
+    fn mesh(&self) -> Mesh {
+        match *self {
+            Self::Circle(radius) => Circle::new(radius).into(),
+            Self::Rectangle(size) => Rectangle::from_size(size).into(),
+        }
+    }
+}
+
+/// Drawn over the player while focused.
+#[derive(Component)]
+struct HitboxDisplay;
+
+fn show_hitbox(
+    mut commands: Commands,
+    player: Single<(Entity, &ActiveShot, &Hitbox), (With<Player>, Changed<ActiveShot>)>,
+    displays: Query<Entity, With<HitboxDisplay>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let (entity, active, hitbox) = player.into_inner();
+
+    let focused = active.current() == Some(ShotKind::Focus);
+    if !focused {
+        for display in displays.iter() {
+            commands.entity(display).despawn();
+        }
+        return;
+    }
+
+    if !displays.is_empty() {
+        return;
+    }
+
+    commands.entity(entity).with_children(|parent| {
+        parent.spawn((
+            HitboxDisplay,
+            Mesh2d(meshes.add(hitbox.shape.mesh())),
+            MeshMaterial2d(materials.add(Color::from(WHITE))),
+            Transform::from_xyz(0., 0., HITBOX_Z),
+        ));
+        parent.spawn((
+            HitboxDisplay,
+            Mesh2d(meshes.add(Annulus::new(hitbox.graze_radius - 0.5, hitbox.graze_radius))),
+            MeshMaterial2d(materials.add(Color::from(RED.with_alpha(0.5)))),
+            Transform::from_xyz(0., 0., HITBOX_Z - 1.),
+        ));
+    });
+}
+
+/// Written for every bullet that passes within the player's [`Hitbox::graze_radius`].
+#[derive(Event)]
+pub struct GrazeEvent {
+    pub position: Vec2,
+}
+
+/// Fills up with grazes. Every time the meter fills, the player is awarded a bomb.
+#[derive(Default, Resource)]
+pub struct GrazeMeter {
+    charge: f32,
+    total: usize,
+}
+
+impl GrazeMeter {
+    /// How full the meter is, from 0 to 1.
+    pub fn fraction(&self) -> f32 {
+        self.charge / GRAZE_METER
+    }
+
+    /// Bullets grazed this run.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+}
+
+fn reset_meter(mut meter: ResMut<GrazeMeter>) {
+    *meter = GrazeMeter::default();
+}
+
+fn charge_meter(
+    mut reader: EventReader<GrazeEvent>,
+    mut meter: ResMut<GrazeMeter>,
+    mut bombs: ResMut<Bombs>,
+) {
+    for _ in reader.read() {
+        meter.total += 1;
+        meter.charge += GRAZE_CHARGE;
+        if meter.charge >= GRAZE_METER {
+            meter.charge -= GRAZE_METER;
+            bombs.add(1);
+        }
+    }
+}
@@ -0,0 +1,197 @@
+#![allow(clippy::type_complexity)]
+#![allow(clippy::too_many_arguments)]
+
+use avian2d::prelude::{Gravity, PhysicsLayer};
+use bevy::app::{FixedMainScheduleOrder, PluginGroupBuilder};
+use bevy::ecs::schedule::ScheduleLabel;
+use bevy::prelude::*;
+
+mod animation;
+mod assets;
+mod asteroids;
+mod auto_collider;
+mod background;
+mod bomb;
+pub mod boss;
+mod bounds;
+pub mod bullet;
+pub mod campaign;
+mod characters;
+mod color;
+mod effects;
+mod end;
+pub mod enemy;
+mod fire;
+pub mod harness;
+pub mod health;
+pub mod hitbox;
+mod input;
+pub mod lives;
+mod minions;
+mod music;
+mod opening;
+mod particles;
+mod pickups;
+pub mod player;
+pub mod points;
+pub mod replay;
+pub mod rng;
+mod sampler;
+mod selection;
+pub mod ship;
+mod sprites;
+pub mod stats;
+mod text;
+mod textbox;
+mod tween;
+mod ui;
+
+pub const WIDTH: f32 = 128.;
+pub const HEIGHT: f32 = 192.;
+
+pub const RESOLUTION_SCALE: f32 = 4.;
+pub const RES_WIDTH: f32 = WIDTH;
+pub const RES_HEIGHT: f32 = HEIGHT;
+
+pub const METER: f32 = 8.;
+
+pub const SKIP_WAVES: bool = false;
+pub const PLAYER_INVINCIBLE: bool = true;
+
+/// Every plugin that makes up the game itself, without a window, renderer, or audio device.
+///
+/// The binary adds `DefaultPlugins` and the presentation plugins on top. Tests run it under
+/// [`harness::HeadlessPlugins`].
+pub struct GamePlugins;
+
+impl PluginGroup for GamePlugins {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add(CorePlugin)
+            .add_group(bevy_tween::DefaultTweenPlugins)
+            .add(bevy_enhanced_input::EnhancedInputPlugin)
+            // the average object (bullet) is 8 ppx.
+            .add_group(avian2d::PhysicsPlugins::new(Avian).with_length_unit(METER))
+            .add(physics::PhysicsPlugin)
+            .add(bevy_optix::shake::ScreenShakePlugin)
+            .add(animation::AnimationPlugin)
+            .add(music::MusicPlugin)
+            .add(assets::AssetPlugin)
+            .add(pickups::PickupPlugin)
+            .add(characters::CharacterPlugin)
+            .add(player::PlayerPlugin)
+            .add(lives::LivesPlugin)
+            .add(enemy::EnemyPlugin)
+            .add(textbox::TextboxPlugin)
+            .add(background::BackgroundPlugin)
+            .add(bullet::BulletPlugin)
+            .add(health::HealthPlugin)
+            .add(hitbox::HitboxPlugin)
+            .add(auto_collider::AutoColliderPlugin)
+            .add(bounds::ScreenBoundsPlugin)
+            .add(ui::UiPlugin)
+            .add(opening::OpeningPlugin)
+            .add(asteroids::AsteroidPlugin)
+            .add(minions::MinionPlugin)
+            .add(tween::TweenPlugin)
+            .add(selection::SelectionPlugin)
+            .add(ship::ShipPlugin)
+            .add(stats::StatPlugin)
+            .add(end::EndPlugin)
+            .add(campaign::CampaignPlugin)
+            .add(input::InputPlugin)
+            .add(boss::BossPlugin)
+            .add(bomb::BombPlugin)
+            .add(points::PointPlugin)
+            .add(effects::EffectsPlugin)
+            .add(particles::ParticlePlugin)
+            .add(sprites::SpritePlugin)
+            .add(text::TextPlugin)
+            .add(rng::RngPlugin)
+            .add(replay::ReplayPlugin)
+    }
+}
+
+struct CorePlugin;
+
+impl Plugin for CorePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_schedule(Avian)
+            .insert_resource(Gravity(Vec2::ZERO))
+            .init_state::<GameState>()
+            .add_systems(Startup, finish_startup.run_if(in_state(GameState::Startup)))
+            .add_systems(
+                Update,
+                despawn_on_restart.run_if(in_state(GameState::Restart)),
+            )
+            .add_systems(
+                Update,
+                (
+                    enter_game.run_if(in_state(GameState::StartGame)),
+                    enter_start_game.run_if(in_state(GameState::Restart)),
+                ),
+            );
+
+        // the defalt schedule for Avian is `FixedPostUpdate`, but I wanted something easier to type,
+        // so it is set to `Avian`
+        app.world_mut()
+            .resource_mut::<FixedMainScheduleOrder>()
+            .insert_after(FixedPostUpdate, Avian);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, ScheduleLabel)]
+pub struct Avian;
+
+// this many layers is probably not necessary
+#[derive(Default, Clone, Copy, PartialEq, Eq, PhysicsLayer)]
+pub enum Layer {
+    #[default]
+    Default,
+    Bounds,
+    Bullet,
+    Player,
+    Enemy,
+    Debris,
+    Collectable,
+    Miners,
+}
+
+fn finish_startup(mut commands: Commands) {
+    #[cfg(not(debug_assertions))]
+    commands.set_state(GameState::Opening);
+    #[cfg(debug_assertions)]
+    commands.set_state(GameState::ShipSelect);
+}
+
+fn enter_game(mut commands: Commands) {
+    commands.set_state(GameState::Game)
+}
+
+fn enter_start_game(mut commands: Commands) {
+    commands.set_state(GameState::StartGame)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, States)]
+pub enum GameState {
+    #[default]
+    Startup,
+    Opening,
+    ShipSelect,
+    StartGame,
+    Restart,
+    Game,
+    Selection,
+}
+
+#[derive(Default, Component)]
+pub struct DespawnRestart;
+
+fn despawn_on_restart(
+    mut commands: Commands,
+    entities: Query<Entity, (With<DespawnRestart>, Without<ChildOf>)>,
+) {
+    for entity in entities.iter() {
+        commands.entity(entity).try_despawn();
+    }
+}
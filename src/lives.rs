@@ -0,0 +1,296 @@
+//! Player lives, respawning, and continuing once they run out.
+//!
+//! Every death takes one of the player's [`Lives`], scatters part of their
+//! [`Power`](crate::player::Power) and clears the enemy bullets around them. The
+//! player comes back after a short delay, [`Invincible`](crate::health::Invincible) for a few
+//! seconds. With no lives left, a countdown gives the player the chance to spend one of their
+//! [`Credits`] to continue, which refills their lives but resets their score.
+use crate::{
+    DespawnRestart, GameState, RESOLUTION_SCALE,
+    bullet::{Bullet, BulletSource, PlayerBullet, light::LightBullets},
+    effects::{Explosion, SpawnExplosion},
+    end,
+    health::Dead,
+    input::Interact,
+    player::{self, POWER_LOSS, Player, Power},
+    points::Points,
+    rng::{GameRng, RngStream},
+    ship::{ShipDefinition, Ships},
+    tween::{TimeMult, time_mult},
+};
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+use bevy_enhanced_input::events::Fired;
+use bevy_optix::pixel_perfect::HIGH_RES_LAYER;
+use bevy_tween::{
+    prelude::{AnimationBuilderExt, EaseKind},
+    tween::{IntoTarget, TargetResource},
+};
+use std::time::Duration;
+
+const STARTING_LIVES: usize = 3;
+const STARTING_CREDITS: usize = 3;
+
+/// Seconds between a death and the player coming back.
+const RESPAWN_SECS: f32 = 1.5;
+const RESPAWN_INVINCIBLE_SECS: f32 = 3.;
+/// Enemy bullets this close to where the player died are cleared.
+const CLEAR_RADIUS: f32 = 48.;
+
+/// Seconds the player has to continue.
+const CONTINUE_SECS: f32 = 10.;
+/// Seconds before [`Interact`] continues. It shares a gamepad button with shooting, so this keeps
+/// a player still firing from spending a credit by accident.
+const CONTINUE_GRACE_SECS: f32 = 0.5;
+const CONTINUE_Z: f32 = 510.;
+
+pub struct LivesPlugin;
+
+impl Plugin for LivesPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Lives(STARTING_LIVES))
+            .insert_resource(Credits(STARTING_CREDITS))
+            .add_systems(OnEnter(GameState::StartGame), insert_lives)
+            .add_systems(First, handle_death)
+            .add_systems(
+                Update,
+                (respawn, update_continue).run_if(in_state(GameState::Game)),
+            )
+            .add_observer(continue_game);
+    }
+}
+
+/// Lives left, including the current one.
+#[derive(Resource)]
+pub struct Lives(usize);
+
+impl Lives {
+    pub fn get(&self) -> usize {
+        self.0
+    }
+}
+
+/// Continues left.
+#[derive(Resource)]
+pub struct Credits(usize);
+
+impl Credits {
+    pub fn get(&self) -> usize {
+        self.0
+    }
+}
+
+fn insert_lives(mut commands: Commands) {
+    commands.insert_resource(Lives(STARTING_LIVES));
+    commands.insert_resource(Credits(STARTING_CREDITS));
+    commands.remove_resource::<Respawn>();
+}
+
+/// Counts down to the player coming back.
+#[derive(Resource)]
+struct Respawn(Timer);
+
+impl Respawn {
+    fn after(secs: f32) -> Self {
+        Self(Timer::from_seconds(secs, TimerMode::Once))
+    }
+}
+
+fn handle_death(
+    mut commands: Commands,
+    server: Res<AssetServer>,
+    player: Single<(Entity, &Transform), (With<Player>, With<Dead>)>,
+    bullets: Query<(Entity, &Transform), (With<Bullet>, Without<PlayerBullet>)>,
+    mut light_bullets: ResMut<LightBullets>,
+    mut explosions: EventWriter<SpawnExplosion>,
+    mut lives: ResMut<Lives>,
+    credits: Res<Credits>,
+    mut power: ResMut<Power>,
+    mut rng: ResMut<GameRng>,
+) {
+    let (player, transform) = player.into_inner();
+    let position = transform.translation.xy();
+    commands.entity(player).despawn();
+
+    let lost = power.lose(POWER_LOSS);
+    player::scatter_power(
+        &mut commands,
+        rng.stream(RngStream::Pickups),
+        lost,
+        position,
+    );
+
+    let cleared = bullets
+        .iter()
+        .filter(|(_, transform)| transform.translation.xy().distance(position) <= CLEAR_RADIUS)
+        .map(|(entity, transform)| {
+            commands.entity(entity).despawn();
+            transform.translation.xy()
+        })
+        .chain(light_bullets.remove_within(BulletSource::Enemy, position, CLEAR_RADIUS));
+    for position in cleared {
+        explosions.write(SpawnExplosion {
+            position,
+            explosion: Explosion::Small,
+        });
+    }
+
+    lives.0 = lives.0.saturating_sub(1);
+    if lives.0 > 0 {
+        commands.insert_resource(Respawn::after(RESPAWN_SECS));
+    } else if credits.0 > 0 {
+        slow_to_stop(&mut commands).insert(Slowdown);
+        spawn_continue_screen(&mut commands, &server);
+    } else {
+        slow_to_stop(&mut commands);
+        show_loose_screen(&mut commands);
+    }
+}
+
+/// Bring the game to a stop over a couple of seconds.
+fn slow_to_stop<'a>(commands: &'a mut Commands) -> EntityCommands<'a> {
+    let mut tween = commands.animation().insert_tween_here(
+        Duration::from_secs_f32(2.),
+        EaseKind::QuadraticIn,
+        TargetResource.with(time_mult(1., 0.)),
+    );
+    tween.insert(DespawnRestart);
+    tween
+}
+
+fn show_loose_screen(commands: &mut Commands) {
+    commands.queue(|world: &mut World| world.run_system_once(end::show_loose_screen));
+}
+
+fn respawn(
+    mut commands: Commands,
+    time: Res<Time>,
+    respawn: Option<ResMut<Respawn>>,
+    ships: Res<Ships>,
+    definitions: Res<Assets<ShipDefinition>>,
+) {
+    let Some(mut respawn) = respawn else {
+        return;
+    };
+    if !respawn.0.tick(time.delta()).finished() {
+        return;
+    }
+    commands.remove_resource::<Respawn>();
+
+    let Some(ship) = ships.selected(&definitions) else {
+        error!("the selected ship is not loaded");
+        return;
+    };
+    let player = player::spawn_ship(&mut commands, ship);
+    player::invincibility(
+        &mut commands,
+        player,
+        Color::srgba(1., 1., 1., 0.2),
+        RESPAWN_INVINCIBLE_SECS,
+    );
+}
+
+/// The tween stopping the game while the player decides whether to continue.
+#[derive(Component)]
+struct Slowdown;
+
+#[derive(Component)]
+#[require(DespawnRestart)]
+struct ContinueScreen;
+
+#[derive(Component)]
+struct ContinueCountdown(Timer);
+
+fn spawn_continue_screen(commands: &mut Commands, server: &AssetServer) {
+    let font = |size: f32| TextFont {
+        font_size: size,
+        font: server.load("fonts/joystix.otf"),
+        ..Default::default()
+    };
+
+    commands.spawn((
+        ContinueScreen,
+        Sprite::from_image(server.load("continue.png")),
+        Transform::from_xyz(0., 0., CONTINUE_Z),
+    ));
+    commands.spawn((
+        ContinueScreen,
+        HIGH_RES_LAYER,
+        Text2d::new("CONTINUE?"),
+        font(30.),
+        Transform::from_xyz(0., 20. * RESOLUTION_SCALE, CONTINUE_Z),
+    ));
+    commands.spawn((
+        ContinueScreen,
+        ContinueCountdown(Timer::from_seconds(CONTINUE_SECS, TimerMode::Once)),
+        HIGH_RES_LAYER,
+        Text2d::default(),
+        font(48.),
+        Transform::from_xyz(0., 0., CONTINUE_Z),
+    ));
+    commands.spawn((
+        ContinueScreen,
+        CreditsText,
+        HIGH_RES_LAYER,
+        Text2d::default(),
+        font(16.),
+        Transform::from_xyz(0., -20. * RESOLUTION_SCALE, CONTINUE_Z),
+    ));
+}
+
+#[derive(Component)]
+struct CreditsText;
+
+fn update_continue(
+    mut commands: Commands,
+    // the game is slowed to a stop while counting down
+    time: Res<Time<Real>>,
+    countdown: Option<Single<(&mut ContinueCountdown, &mut Text2d)>>,
+    mut credits_text: Query<&mut Text2d, (With<CreditsText>, Without<ContinueCountdown>)>,
+    screen: Query<Entity, With<ContinueScreen>>,
+    credits: Res<Credits>,
+) {
+    let Some(countdown) = countdown else {
+        return;
+    };
+    let (mut countdown, mut text) = countdown.into_inner();
+
+    countdown.0.tick(time.delta());
+    text.0 = format!("{:.0}", countdown.0.remaining_secs().ceil());
+    for mut text in credits_text.iter_mut() {
+        text.0 = format!("Credits: {}", credits.0);
+    }
+
+    if countdown.0.finished() {
+        for entity in screen.iter() {
+            commands.entity(entity).despawn();
+        }
+        // the game has already stopped
+        show_loose_screen(&mut commands);
+    }
+}
+
+/// Spend a credit to refill the player's lives, starting the score over.
+fn continue_game(
+    _: Trigger<Fired<Interact>>,
+    mut commands: Commands,
+    countdown: Option<Single<&ContinueCountdown>>,
+    screen: Query<Entity, With<ContinueScreen>>,
+    slowdown: Query<Entity, With<Slowdown>>,
+    mut lives: ResMut<Lives>,
+    mut credits: ResMut<Credits>,
+    mut points: ResMut<Points>,
+    mut time: ResMut<TimeMult>,
+) {
+    if countdown.is_none_or(|countdown| countdown.0.elapsed_secs() < CONTINUE_GRACE_SECS) {
+        return;
+    }
+
+    for entity in screen.iter().chain(slowdown.iter()) {
+        commands.entity(entity).despawn();
+    }
+    credits.0 = credits.0.saturating_sub(1);
+    lives.0 = STARTING_LIVES;
+    points.reset();
+    time.0 = 1.;
+    commands.insert_resource(Respawn::after(0.));
+}
@@ -8,7 +8,7 @@ use crate::bullet::homing::{Heading, HomingTarget, TurnSpeed};
 use crate::bullet::{BulletTimer, Polarity};
 use crate::color::HexColor;
 use crate::effects::Blasters;
-use crate::pickups::{Collectable, Material, PickupEvent, PowerUp, Weapon};
+use crate::pickups::{Collectable, Material, PickupEvent, PowerUp, Scattered, Weapon};
 use crate::player::{AliveContext, NormalShot, PLAYER_SPEED, Player, PowerUpEvent, WeaponRack};
 use crate::sprites::{CellSize, TiltSprite};
 use crate::text::flash_text;
@@ -153,7 +153,7 @@ fn miner_collect(
     //miners: Query<&CollidingEntities, With<Miner>>,
     player: Single<(&CollidingEntities, &Transform), With<Player>>,
     materials: Query<&Material>,
-    pickups: Query<&PowerUp>,
+    pickups: Query<&PowerUp, Without<Scattered>>,
     mut power_ups: EventWriter<PowerUpEvent>,
     mut writer: EventWriter<PickupEvent>,
     time: Res<Time>,
@@ -162,14 +162,18 @@ fn miner_collect(
     let (entities, transform) = player.into_inner();
     timer.0.tick(time.delta());
 
-    for entity in entities
+    for (entity, power) in entities
         .iter()
         .copied()
-        .filter(|entity| pickups.get(*entity).is_ok())
+        .flat_map(|entity| pickups.get(entity).map(|power| (entity, power)))
     {
-        power_ups.write(PowerUpEvent);
+        power_ups.write(PowerUpEvent(power.power()));
         commands.entity(entity).despawn();
 
+        if *power == PowerUp::Small {
+            continue;
+        }
+
         flash_text(
             &mut commands,
             &server,
@@ -1,7 +1,10 @@
+use crate::campaign::{Campaign, StagePhase};
 use crate::enemy::formation::{Formation, FormationEntity};
+use crate::enemy::stage::Stage;
 use crate::{GameState, boss};
 use bevy::prelude::*;
 use bevy_seedling::prelude::*;
+use serde::Deserialize;
 
 const MUSIC_VOLUME: f32 = 0.4;
 
@@ -10,7 +13,7 @@ pub struct MusicPlugin;
 impl Plugin for MusicPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(GameState::Restart), restart)
-            .add_systems(OnEnter(GameState::StartGame), startup)
+            .add_systems(OnEnter(StagePhase::Intro), play_stage_music)
             .add_systems(Update, update_layers);
     }
 }
@@ -21,38 +24,74 @@ fn restart(mut commands: Commands, players: Query<Entity, With<SamplePlayer>>) {
     }
 }
 
-fn startup(mut commands: Commands, server: Res<AssetServer>) {
+/// The tracks a stage plays. Layers fade in and out with what is on screen.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct StageMusic {
+    /// Plays for the whole stage, until the boss arrives.
+    pub base: String,
+    /// Layered over the base while a formation is alive.
+    pub combat: Vec<String>,
+    pub boss: String,
+    /// Layered over the boss track in the boss's second phase.
+    pub boss_b: String,
+}
+
+impl Default for StageMusic {
+    fn default() -> Self {
+        Self {
+            base: "audio/music/robbery_base.wav".into(),
+            combat: vec![
+                "audio/music/robbery_lead.wav".into(),
+                "audio/music/robbery_drums.wav".into(),
+            ],
+            boss: "audio/music/something_imminent.wav".into(),
+            boss_b: "audio/music/something_imminent_arp.wav".into(),
+        }
+    }
+}
+
+fn play_stage_music(
+    mut commands: Commands,
+    server: Res<AssetServer>,
+    campaign: Res<Campaign>,
+    stages: Res<Assets<Stage>>,
+    players: Query<Entity, Or<(With<WaveLayer>, With<BossLayer>)>>,
+) {
+    let Some(stage) = campaign.stage(&stages) else {
+        error!("the current stage is not loaded");
+        return;
+    };
+
+    for entity in players.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    let music = &stage.music;
     commands.spawn((
         WaveLayer,
-        SamplePlayer::new(server.load("audio/music/robbery_base.wav")),
+        SamplePlayer::new(server.load(music.base.clone())),
         PlaybackSettings::LOOP,
         sample_effects![VolumeNode {
             volume: Volume::Linear(MUSIC_VOLUME),
         }],
     ));
 
-    commands.spawn((
-        WaveLayer,
-        WaveCombatLayer,
-        SamplePlayer::new(server.load("audio/music/robbery_lead.wav")),
-        PlaybackSettings::LOOP,
-        sample_effects![VolumeNode {
-            volume: Volume::SILENT,
-        }],
-    ));
-    commands.spawn((
-        WaveLayer,
-        WaveCombatLayer,
-        SamplePlayer::new(server.load("audio/music/robbery_drums.wav")),
-        PlaybackSettings::LOOP,
-        sample_effects![VolumeNode {
-            volume: Volume::SILENT,
-        }],
-    ));
+    for layer in music.combat.iter() {
+        commands.spawn((
+            WaveLayer,
+            WaveCombatLayer,
+            SamplePlayer::new(server.load(layer.clone())),
+            PlaybackSettings::LOOP,
+            sample_effects![VolumeNode {
+                volume: Volume::SILENT,
+            }],
+        ));
+    }
 
     commands.spawn((
         BossLayer,
-        SamplePlayer::new(server.load("audio/music/something_imminent.wav")),
+        SamplePlayer::new(server.load(music.boss.clone())),
         PlaybackSettings::LOOP,
         sample_effects![VolumeNode {
             volume: Volume::SILENT
@@ -62,7 +101,7 @@ fn startup(mut commands: Commands, server: Res<AssetServer>) {
     commands.spawn((
         BossLayer,
         BossBLayer,
-        SamplePlayer::new(server.load("audio/music/something_imminent_arp.wav")),
+        SamplePlayer::new(server.load(music.boss_b.clone())),
         PlaybackSettings::LOOP,
         sample_effects![VolumeNode {
             volume: Volume::SILENT,
@@ -91,10 +130,9 @@ fn update_layers(
     all_boss: Query<&SampleEffects, With<BossLayer>>,
     mut nodes: Query<&mut VolumeNode>,
     formations: Query<&FormationEntity>,
-    boss: Option<Single<&boss::gradius::Gradius>>,
-    boss_b: Option<Single<&boss::gradius::PhaseB>>,
+    boss: Option<Single<&boss::phases::BossPhases>>,
 ) -> Result {
-    if boss.is_none() {
+    let Some(boss) = boss else {
         nodes.get_effect_mut(*wave_base)?.volume = Volume::Linear(MUSIC_VOLUME);
 
         for effects in wave_combat_layers.iter() {
@@ -111,18 +149,21 @@ fn update_layers(
             let mut node = nodes.get_effect_mut(effects)?;
             node.volume = Volume::SILENT;
         }
+
+        return Ok(());
+    };
+
+    nodes.get_effect_mut(*boss_base)?.volume = Volume::Linear(MUSIC_VOLUME);
+    // the second layer comes in every other phase
+    if boss.current() % 2 == 1 {
+        nodes.get_effect_mut(*boss_b_layer)?.volume = Volume::Linear(MUSIC_VOLUME);
     } else {
-        nodes.get_effect_mut(*boss_base)?.volume = Volume::Linear(MUSIC_VOLUME);
-        if boss_b.is_some() {
-            nodes.get_effect_mut(*boss_b_layer)?.volume = Volume::Linear(MUSIC_VOLUME);
-        } else {
-            nodes.get_effect_mut(*boss_b_layer)?.volume = Volume::SILENT;
-        }
+        nodes.get_effect_mut(*boss_b_layer)?.volume = Volume::SILENT;
+    }
 
-        for effects in all_wave.iter() {
-            let mut node = nodes.get_effect_mut(effects)?;
-            node.volume = Volume::SILENT;
-        }
+    for effects in all_wave.iter() {
+        let mut node = nodes.get_effect_mut(effects)?;
+        node.volume = Volume::SILENT;
     }
 
     Ok(())
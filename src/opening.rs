@@ -219,7 +219,7 @@ fn end(mut commands: Commands, opening_entities: Query<Entity, With<OpeningEntit
     for entity in opening_entities.iter() {
         commands.entity(entity).despawn();
     }
-    commands.set_state(GameState::StartGame);
+    commands.set_state(GameState::ShipSelect);
 }
 
 pub struct MandelbrotPlugin;
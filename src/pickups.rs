@@ -4,21 +4,27 @@ use crate::player::Player;
 use crate::sprites::CellSprite;
 use crate::{DespawnRestart, Layer, assets, sprites};
 use avian2d::prelude::*;
-use bevy::color::palettes::css::{LIGHT_BLUE, YELLOW};
+use bevy::color::palettes::css::{LIGHT_BLUE, RED, YELLOW};
 use bevy::ecs::component::HookContext;
 use bevy::ecs::world::DeferredWorld;
 use bevy::prelude::*;
 use bevy_optix::debug::{DebugCircle, DebugRect};
 use rand::Rng;
+use serde::Deserialize;
 
 const PICKUP_SPEED: f32 = 16.;
 
+pub const SMALL_POWER: f32 = 0.05;
+pub const LARGE_POWER: f32 = 1.;
+
 pub struct PickupPlugin;
 
 impl Plugin for PickupPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<PickupEvent>()
-            .add_systems(Update, (pickup_triggered, update_scrolling_pickup));
+        app.add_event::<PickupEvent>().add_systems(
+            Update,
+            (pickup_triggered, update_scrolling_pickup, settle_scattered),
+        );
     }
 }
 
@@ -104,7 +110,7 @@ pub enum Upgrade {
 //    }
 //}
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Component)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Component, Deserialize)]
 #[component(on_add = Self::sprite_hook)]
 pub enum Weapon {
     #[default]
@@ -224,9 +230,9 @@ pub struct ScrollingPickup {
 }
 
 impl ScrollingPickup {
-    pub fn new() -> Self {
+    pub fn new(rng: &mut impl Rng) -> Self {
         Self {
-            index: rand::rng().random_range(0..5),
+            index: rng.random_range(0..5),
             timer: Timer::from_seconds(2., TimerMode::Repeating),
         }
     }
@@ -273,15 +279,72 @@ fn update_scrolling_pickup(
     }
 }
 
-#[derive(Debug, Clone, Copy, Component)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
 #[require(
     Collectable,
-    Collider::circle(8.),
-    CellSprite::new16("ships.png", UVec2::new(3, 0)),
     LinearVelocity(Vec2::NEG_Y * PICKUP_SPEED),
     AngularVelocity(0.1),
 )]
-pub struct PowerUp;
+#[component(on_add = Self::insert_visual)]
+pub enum PowerUp {
+    Small,
+    Large,
+}
+
+impl PowerUp {
+    pub fn power(&self) -> f32 {
+        match self {
+            Self::Small => SMALL_POWER,
+            Self::Large => LARGE_POWER,
+        }
+    }
+
+    fn insert_visual(mut world: DeferredWorld, ctx: HookContext) {
+        let power = *world.get::<PowerUp>(ctx.entity).unwrap();
+        let mut commands = world.commands();
+        let mut entity = commands.entity(ctx.entity);
+        match power {
+            PowerUp::Small => {
+                entity.insert((Collider::circle(4.), DebugCircle::color(2., RED)));
+            }
+            PowerUp::Large => {
+                entity.insert((
+                    Collider::circle(8.),
+                    CellSprite::new16("ships.png", UVec2::new(3, 0)),
+                ));
+            }
+        }
+    }
+}
+
+/// A pickup thrown out with some velocity, e.g. the power scattered when the player dies.
+///
+/// The pickup slows down and starts falling like any other pickup, and can only be collected once
+/// it has settled.
+#[derive(Component)]
+pub struct Scattered(Timer);
+
+impl Scattered {
+    pub fn new(secs: f32) -> Self {
+        Self(Timer::from_seconds(secs, TimerMode::Once))
+    }
+}
+
+fn settle_scattered(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut pickups: Query<(Entity, &mut Scattered, &mut LinearVelocity)>,
+) {
+    for (entity, mut scattered, mut velocity) in pickups.iter_mut() {
+        scattered.0.tick(time.delta());
+        let fall = Vec2::NEG_Y * PICKUP_SPEED;
+        velocity.0 = velocity.0.lerp(fall, scattered.0.fraction());
+        if scattered.0.finished() {
+            velocity.0 = fall;
+            commands.entity(entity).remove::<Scattered>();
+        }
+    }
+}
 
 #[derive(Debug, Clone, Copy, Component)]
 #[require(
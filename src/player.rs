@@ -5,14 +5,19 @@ use crate::{
         emitter::{BulletModifiers, EmitterState},
         player::{PlayerFocusEmitter, PlayerGattlingEmitter},
     },
-    effects::{Blasters, Explosion},
-    end,
+    effects::{Blasters, Explosion, hit::HitFeedback},
     enemy::Enemy,
-    health::{DamageEvent, Dead, Health, HealthSet, Invincible, Shield},
+    health::{
+        DamageDealt, DamageEvent, Health, HealthSet, Invincible, Shield, ShieldBroken, ShieldRegen,
+    },
+    hitbox::Hitbox,
     minions::{Gunner, GunnerWeapon},
-    pickups::{Material, PickupEvent, Upgrade, Weapon},
+    pickups::{
+        LARGE_POWER, Material, PickupEvent, PowerUp, SMALL_POWER, Scattered, Upgrade, Weapon,
+    },
+    ship::{Ship, ShipDefinition, Ships},
     sprites::{CellSize, TiltSprite},
-    tween::{OnEnd, TimeMult, time_mult},
+    tween::{OnEnd, TimeMult},
 };
 use avian2d::prelude::*;
 use bevy::{
@@ -25,30 +30,38 @@ use bevy_optix::{
     glitch::{GlitchIntensity, GlitchSettings, glitch_intensity},
     pixel_perfect::OuterCamera,
     post_process::PostProcessCommand,
-    shake::TraumaCommands,
 };
 use bevy_seedling::prelude::*;
 use bevy_sequence::combinators::delay::run_after;
 use bevy_tween::{
     interpolate::sprite_color,
     prelude::{AnimationBuilderExt, EaseKind, Repeat, RepeatStyle},
-    tween::{IntoTarget, TargetResource},
+    tween::IntoTarget,
 };
+use rand::Rng;
 use std::{
-    f32::{self},
+    f32::{self, consts::FRAC_PI_3},
     time::Duration,
 };
 
-pub const PLAYER_HEALTH: f32 = 3.0;
+/// A hit that gets past the [`Shield`] costs a life.
+pub const PLAYER_HEALTH: f32 = 1.0;
+/// Hits the player's [`Shield`] absorbs when fully charged.
+pub const PLAYER_SHIELD: f32 = 2.0;
+/// Seconds without being hit before the shield recharges.
+const SHIELD_REGEN_DELAY: f32 = 4.;
+const SHIELD_REGEN_RATE: f32 = 0.125;
+/// Shield charged by each [`Material::Shield`].
+const SHIELD_PER_MATERIAL: f32 = 0.1;
 const PLAYER_EASE_DUR: f32 = 1.;
 pub const PLAYER_SPEED: f32 = 110.;
-pub const PLAYER_FOCUS_SPEED: f32 = 80.;
 
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<PowerUpEvent>()
+            .init_resource::<Power>()
             .insert_resource(WeaponRack::default())
             .add_systems(OnEnter(GameState::Restart), restart)
             .add_systems(OnEnter(GameState::StartGame), spawn_player)
@@ -67,7 +80,6 @@ impl Plugin for PlayerPlugin {
                     .after(HealthSet)
                     .run_if(in_state(GameState::Game)),
             )
-            .add_systems(First, handle_death)
             .add_input_context::<AliveContext>()
             .add_observer(apply_movement)
             .add_observer(stop_movement)
@@ -78,13 +90,27 @@ impl Plugin for PlayerPlugin {
     }
 }
 
-fn spawn_player(mut commands: Commands) {
+fn spawn_player(
+    mut commands: Commands,
+    ships: Res<Ships>,
+    definitions: Res<Assets<ShipDefinition>>,
+) {
+    let Some(ship) = ships.selected(&definitions) else {
+        error!("the selected ship is not loaded");
+        return;
+    };
+
     if crate::SKIP_WAVES {
-        commands.insert_resource(PowerUps(2));
+        commands.insert_resource(Power(2.));
     } else {
-        commands.insert_resource(PowerUps::default());
+        commands.insert_resource(Power::default());
     }
 
+    spawn_ship(&mut commands, ship);
+}
+
+/// Spawn the player as `ship`, flying in from the bottom of the screen.
+pub(crate) fn spawn_ship(commands: &mut Commands, ship: &ShipDefinition) -> Entity {
     let layers = if crate::PLAYER_INVINCIBLE {
         Player::invincible_layers()
     } else {
@@ -94,6 +120,16 @@ fn spawn_player(mut commands: Commands) {
     let player = commands
         .spawn((
             Player,
+            ship.hitbox,
+            TiltSprite {
+                path: "ships.png",
+                size: CellSize::Eight,
+                //
+                left: ship.sprite.left,
+                center: ship.sprite.center,
+                right: ship.sprite.right,
+            },
+            Ship(ship.clone()),
             Transform::from_xyz(0., -HEIGHT / 6., 0.),
             Blasters(const { &[Vec3::new(0., -6., -1.)] }),
             layers,
@@ -115,7 +151,7 @@ fn spawn_player(mut commands: Commands) {
         move |mut commands: Commands| {
             commands.entity(player).remove::<BlockControls>();
         },
-        &mut commands,
+        commands,
     );
 
     #[cfg(not(debug_assertions))]
@@ -134,62 +170,112 @@ fn spawn_player(mut commands: Commands) {
                 )),
             );
     }
+
+    player
 }
 
 fn enemy_collision(
     mut writer: EventWriter<DamageEvent>,
     player: Single<(Entity, &CollidingEntities), With<Player>>,
-    enemies: Query<&Enemy>,
+    enemies: Query<Entity, With<Enemy>>,
 ) {
     let (entity, collisions) = player.into_inner();
-    if enemies.iter_many(collisions.iter()).count() > 0 {
-        writer.write(DamageEvent { entity, damage: 1. });
+    if let Some(enemy) = enemies.iter_many(collisions.iter()).next() {
+        writer.write(DamageEvent::new(entity, 1.).source(enemy));
     }
 }
 
+/// Collected power, see [`PowerUp`](crate::pickups::PowerUp).
 #[derive(Event)]
-pub struct PowerUpEvent;
+pub struct PowerUpEvent(pub f32);
 
+pub const MAX_POWER: f32 = 4.;
+/// Portion of the player's power lost, and scattered, on death.
+pub(crate) const POWER_LOSS: f32 = 0.5;
+/// Most small pickups a single death can scatter, the rest are merged into large ones.
+const MAX_SCATTERED: usize = 8;
+
+/// The player's power, from 0 to [`MAX_POWER`].
+///
+/// Power selects the player's shots from the ship's [`ShotTable`](crate::ship::ShotTable).
 #[derive(Default, Resource)]
-pub struct PowerUps(usize);
+pub struct Power(f32);
 
-impl PowerUps {
-    pub fn get(&self) -> usize {
+impl Power {
+    pub fn get(&self) -> f32 {
         self.0
     }
+
+    pub fn add(&mut self, power: f32) {
+        self.0 = (self.0 + power).min(MAX_POWER);
+    }
+
+    /// Remove a `portion` of the current power, returning how much was lost.
+    pub fn lose(&mut self, portion: f32) -> f32 {
+        let lost = self.0 * portion;
+        self.0 -= lost;
+        lost
+    }
 }
 
 fn handle_powerups(
     mut commands: Commands,
     server: Res<AssetServer>,
-    mut power_ups: ResMut<PowerUps>,
+    mut power: ResMut<Power>,
     mut reader: EventReader<PowerUpEvent>,
-    mut player: Single<&mut BulletModifiers, With<Player>>,
     #[cfg(debug_assertions)] input: Res<ButtonInput<KeyCode>>,
 ) {
     #[cfg(debug_assertions)]
     if input.just_pressed(KeyCode::Digit9) {
-        power_ups.0 += 1;
+        power.add(1.);
     }
 
-    for _ in reader.read() {
-        commands.spawn((
-            SamplePlayer::new(server.load("audio/sfx/ring.wav")),
-            PlaybackSettings {
-                volume: Volume::Linear(0.25),
-                ..PlaybackSettings::ONCE
-            },
-        ));
+    for event in reader.read() {
+        let level = power.get().floor();
+        power.add(event.0);
 
-        power_ups.0 += 1;
+        if power.get().floor() > level {
+            commands.spawn((
+                SamplePlayer::new(server.load("audio/sfx/ring.wav")),
+                PlaybackSettings {
+                    volume: Volume::Linear(0.25),
+                    ..PlaybackSettings::ONCE
+                },
+            ));
+        }
+    }
+}
 
-        //match power_ups.0 {
-        //    0 => unreachable!(),
-        //    1..=4 => {
-        //        player.damage += 0.1;
-        //    }
-        //    _ => error!("power up [{}] not handled", power_ups.0),
-        //}
+/// Throw the lost power out around the player as pickups, large ones first.
+///
+/// Small pickups past [`MAX_SCATTERED`] are rounded up into large ones.
+pub(crate) fn scatter_power(
+    commands: &mut Commands,
+    rng: &mut impl Rng,
+    lost: f32,
+    position: Vec2,
+) {
+    let mut large = (lost / LARGE_POWER).floor() as usize;
+    let mut small = ((lost - large as f32 * LARGE_POWER) / SMALL_POWER).round() as usize;
+
+    let room = MAX_SCATTERED.saturating_sub(large);
+    if small > room {
+        let remainder = (small - room) as f32 * SMALL_POWER;
+        large += (remainder / LARGE_POWER).ceil() as usize;
+        small = room;
+    }
+
+    let pickups = std::iter::repeat_n(PowerUp::Large, large)
+        .chain(std::iter::repeat_n(PowerUp::Small, small));
+    for power in pickups {
+        let angle = rng.random_range(-FRAC_PI_3..FRAC_PI_3);
+        let speed = rng.random_range(60.0..100.0);
+        commands.spawn((
+            power,
+            Scattered::new(0.75),
+            Transform::from_translation(position.extend(1.)),
+            LinearVelocity(Vec2::from_angle(angle).rotate(Vec2::Y) * speed),
+        ));
     }
 }
 
@@ -198,16 +284,18 @@ fn handle_powerups(
     Transform,
     Visibility,
     LinearVelocity,
-    Shield::full(0.),
+    Shield::full(PLAYER_SHIELD),
+    ShieldRegen::new(SHIELD_REGEN_DELAY, SHIELD_REGEN_RATE),
     Health::full(PLAYER_HEALTH),
     RigidBody::Dynamic,
-    Collider::rectangle(2., 2.),
+    Hitbox,
     CollidingEntities,
     BulletModifiers,
     Materials,
     DespawnRestart,
     CollisionLayers = Self::layers(),
     Explosion::Big,
+    HitFeedback = HitFeedback::PLAYER,
     ActiveShot::default(),
 )]
 #[component(on_add = Self::on_add)]
@@ -280,14 +368,6 @@ impl Player {
 
                     commands.entity(ctx.entity).insert((
                         actions,
-                        TiltSprite {
-                            path: "ships.png",
-                            size: CellSize::Eight,
-                            //
-                            left: UVec2::new(0, 0),
-                            center: UVec2::new(1, 0),
-                            right: UVec2::new(2, 0),
-                        },
                         BulletTimer {
                             timer: Timer::new(Duration::from_millis(250), TimerMode::Repeating),
                         },
@@ -303,24 +383,29 @@ pub struct AliveContext;
 
 #[derive(Debug, InputAction)]
 #[input_action(output = Vec2)]
-struct MoveAction;
+pub struct MoveAction;
 
 #[derive(Component)]
 pub struct BlockControls;
 
 fn apply_movement(
     trigger: Trigger<Fired<MoveAction>>,
-    player: Single<(&mut LinearVelocity, &ActiveShot, Option<&BlockControls>), With<Player>>,
+    player: Single<
+        (
+            &mut LinearVelocity,
+            &ActiveShot,
+            &Ship,
+            Option<&BlockControls>,
+        ),
+        With<Player>,
+    >,
 ) {
-    let (mut velocity, active_shot, blocked) = player.into_inner();
+    let (mut velocity, active_shot, ship, blocked) = player.into_inner();
 
     if blocked.is_none() {
-        let speed = match active_shot.0.last() {
-            Some(kind) => match kind {
-                ShotKind::Normal => PLAYER_SPEED,
-                ShotKind::Focus => PLAYER_FOCUS_SPEED,
-            },
-            None => PLAYER_SPEED,
+        let speed = match active_shot.current() {
+            Some(ShotKind::Focus) => ship.0.focus_speed,
+            Some(ShotKind::Normal) | None => ship.0.speed,
         };
         velocity.0 = trigger.value.clamp_length(0., 1.) * speed;
     }
@@ -360,8 +445,15 @@ pub enum ShotKind {
     Focus,
 }
 
+/// The shots being held, the last one is the one firing.
 #[derive(Default, Component)]
-struct ActiveShot(Vec<ShotKind>);
+pub struct ActiveShot(Vec<ShotKind>);
+
+impl ActiveShot {
+    pub fn current(&self) -> Option<ShotKind> {
+        self.0.last().copied()
+    }
+}
 
 fn start_normal_shot(
     _: Trigger<Started<NormalShot>>,
@@ -645,19 +737,6 @@ fn zero_rotation(mut player: Single<&mut Transform, With<Player>>) {
     player.rotation = Quat::default();
 }
 
-fn handle_death(mut commands: Commands, player: Single<Entity, (With<Player>, With<Dead>)>) {
-    commands.entity(*player).despawn();
-    commands.queue(|world: &mut World| world.run_system_once(end::show_loose_screen));
-    commands
-        .animation()
-        .insert_tween_here(
-            Duration::from_secs_f32(2.),
-            EaseKind::QuadraticIn,
-            TargetResource.with(time_mult(1., 0.)),
-        )
-        .insert(DespawnRestart);
-}
-
 fn restart(mut commands: Commands, mut time: ResMut<TimeMult>) {
     commands.insert_resource(WeaponRack::default());
     time.0 = 1.;
@@ -717,67 +796,82 @@ fn handle_pickups(
             PickupEvent::Upgrade(Upgrade::Juice(j)) => mods.damage += *j,
             PickupEvent::Material(mat) => match mat {
                 Material::Parts => materials.0 += 1,
-                Material::Shield => shield.heal(1. / 10.),
+                Material::Shield => shield.heal(SHIELD_PER_MATERIAL),
             },
         }
     }
 }
 
+/// Seconds of invincibility after being hit.
+const HIT_INVINCIBLE_SECS: f32 = 1.5;
+/// Seconds of invincibility after a hit breaks the player's [`Shield`].
+const SHIELD_BROKEN_INVINCIBLE_SECS: f32 = 2.5;
+/// Seconds for the player's sprite to flicker to a color and back.
+const FLICKER_SECS: f32 = 0.25;
+
 #[derive(Component)]
 struct FlickerAnimation;
 
 fn handle_damage(
     mut commands: Commands,
-    mut reader: EventReader<DamageEvent>,
-    player: Single<(Entity, Ref<Shield>, Ref<Health>), (With<Player>, Without<Invincible>)>,
+    mut reader: EventReader<DamageDealt>,
+    player: Single<Entity, (With<Player>, Without<Invincible>)>,
 ) {
-    let (player, shield, _health) = player.into_inner();
+    let player = player.into_inner();
 
-    if reader.read().any(|e| e.entity == player) {
-        commands.entity(player).insert(Invincible);
-
-        let color = if shield.is_changed() && shield.empty() {
-            BLUE.into()
+    if let Some(hit) = reader.read().find(|e| e.entity == player) {
+        let (color, secs) = if hit.shield_broke {
+            (BLUE.into(), SHIELD_BROKEN_INVINCIBLE_SECS)
         } else {
-            RED.into()
+            (RED.into(), HIT_INVINCIBLE_SECS)
         };
-        let flicker = commands
-            .animation()
-            .repeat(Repeat::times(3))
-            .repeat_style(RepeatStyle::PingPong)
-            .insert_tween_here(
-                Duration::from_secs_f32(0.25),
-                EaseKind::Linear,
-                player.into_target().with(sprite_color(WHITE.into(), color)),
-            )
-            .insert(FlickerAnimation)
-            .id();
-        commands.entity(player).add_child(flicker);
-
-        run_after(
-            Duration::from_secs_f32(1.5),
-            |mut commands: Commands,
-             mut player: Single<(Entity, &mut Sprite), With<Player>>,
-             animation: Single<Entity, With<FlickerAnimation>>| {
-                commands.entity(*animation).despawn();
-                commands.entity(player.0).remove::<Invincible>();
-                player.1.color = WHITE.into();
-            },
-            &mut commands,
-        );
+        invincibility(&mut commands, player, color, secs);
     }
 }
 
+/// Make the player [`Invincible`] for `secs`, flickering between white and `color`.
+pub(crate) fn invincibility(commands: &mut Commands, player: Entity, color: Color, secs: f32) {
+    commands.entity(player).insert(Invincible);
+
+    let flicker = commands
+        .animation()
+        .repeat(Repeat::times(
+            (secs / (FLICKER_SECS * 2.)).round().max(1.) as usize
+        ))
+        .repeat_style(RepeatStyle::PingPong)
+        .insert_tween_here(
+            Duration::from_secs_f32(FLICKER_SECS),
+            EaseKind::Linear,
+            player.into_target().with(sprite_color(WHITE.into(), color)),
+        )
+        .insert(FlickerAnimation)
+        .id();
+    commands.entity(player).add_child(flicker);
+
+    run_after(
+        Duration::from_secs_f32(secs),
+        move |mut commands: Commands, mut sprites: Query<&mut Sprite, With<Player>>| {
+            // both are gone if the player died in the meantime
+            commands.entity(flicker).try_despawn();
+            if let Ok(mut sprite) = sprites.get_mut(player) {
+                commands.entity(player).remove::<Invincible>();
+                sprite.color = WHITE.into();
+            }
+        },
+        commands,
+    );
+}
+
 fn health_effects(
     mut commands: Commands,
-    server: Res<AssetServer>,
     mut reader: EventReader<DamageEvent>,
-    player: Single<(Entity, Ref<Shield>, Ref<Health>), (With<Player>, Without<Invincible>)>,
+    mut broken: EventReader<ShieldBroken>,
+    player: Single<(Entity, Ref<Health>), (With<Player>, Without<Invincible>)>,
     camera: Single<Entity, With<OuterCamera>>,
 ) {
-    let (player, shield, health) = player.into_inner();
+    let (player, health) = player.into_inner();
 
-    if shield.is_changed() && shield.empty() {
+    if broken.read().any(|e| e.entity == player) {
         let mask = commands
             .spawn((
                 DespawnRestart,
@@ -835,32 +929,5 @@ fn health_effects(
                 camera.into_target().with(glitch_intensity(0.3, 0.0)),
             )
             .insert(on_end);
-
-        commands.add_trauma(0.15);
-        //commands
-        //    .animation()
-        //    .insert_tween_here(
-        //        Duration::from_secs_f32(0.25),
-        //        EaseKind::Linear,
-        //        TargetResource.with(time_mult(0.25, 1.)),
-        //    )
-        //    .insert(DespawnRestart);
-
-        commands.spawn((
-            SamplePlayer::new(server.load("audio/sfx/melee.wav")),
-            PitchRange(0.98..1.02),
-            PlaybackSettings {
-                volume: Volume::Linear(0.25),
-                ..PlaybackSettings::ONCE
-            },
-        ));
-        commands.spawn((
-            SamplePlayer::new(server.load("audio/sfx/player_damage.wav")),
-            //PitchRange(0.98..1.02),
-            PlaybackSettings {
-                volume: Volume::Linear(0.25),
-                ..PlaybackSettings::ONCE
-            },
-        ));
     }
 }
@@ -1,5 +1,13 @@
+//! Points, and the chain multiplying them.
+//!
+//! Every kill within [`CHAIN_SECS`] of the last one adds to the [`Chain`], and every
+//! [`CHAIN_STEP`] kills raise the multiplier applied to kills. Getting hit or bombing drops the
+//! chain. Kills close to the player are worth double.
+use crate::bomb::BombDetonated;
 use crate::color::HexColor;
 use crate::enemy::EnemyDeathEvent;
+use crate::health::DamageDealt;
+use crate::player::Player;
 use crate::text::flash_text;
 use crate::{GameState, RESOLUTION_SCALE};
 use bevy::prelude::*;
@@ -9,19 +17,32 @@ use std::usize;
 pub const COLOR: HexColor = HexColor(0xfff540);
 pub const POINT_TEXT_Z: f32 = 500.;
 
+/// Seconds to make the next kill in before the chain drops.
+pub const CHAIN_SECS: f32 = 2.;
+/// Kills for each step of the multiplier.
+pub const CHAIN_STEP: usize = 5;
+pub const MAX_MULTIPLIER: usize = 8;
+/// Kills this close to the player are point-blank.
+const POINT_BLANK_RADIUS: f32 = 32.;
+
 pub struct PointPlugin;
 
 impl Plugin for PointPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<PointEvent>()
             .insert_resource(Points(0))
+            .init_resource::<Chain>()
             .add_systems(OnEnter(GameState::Restart), restart)
-            .add_systems(PostUpdate, (score_enemy_death, point_effects).chain());
+            .add_systems(
+                PostUpdate,
+                (update_chain, score_enemy_death, point_effects).chain(),
+            );
     }
 }
 
 fn restart(mut commands: Commands) {
     commands.insert_resource(Points(0));
+    commands.insert_resource(Chain::default());
 }
 
 #[derive(Resource)]
@@ -31,23 +52,125 @@ impl Points {
     pub fn get(&self) -> usize {
         self.0
     }
+
+    pub fn reset(&mut self) {
+        self.0 = 0;
+    }
 }
 
 #[derive(Event)]
 pub struct PointEvent {
-    pub points: usize,
+    /// Points before the multiplier.
+    pub base: usize,
+    pub multiplier: usize,
     pub position: Vec2,
 }
 
+impl PointEvent {
+    pub fn new(base: usize, position: Vec2) -> Self {
+        Self {
+            base,
+            multiplier: 1,
+            position,
+        }
+    }
+
+    pub fn multiplied(mut self, multiplier: usize) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Points scored.
+    pub fn points(&self) -> usize {
+        self.base * self.multiplier
+    }
+}
+
+/// Kills made in quick succession.
+#[derive(Resource)]
+pub struct Chain {
+    kills: usize,
+    best: usize,
+    timer: Timer,
+}
+
+impl Default for Chain {
+    fn default() -> Self {
+        Self {
+            kills: 0,
+            best: 0,
+            timer: Timer::from_seconds(CHAIN_SECS, TimerMode::Once),
+        }
+    }
+}
+
+impl Chain {
+    pub fn kills(&self) -> usize {
+        self.kills
+    }
+
+    /// The longest chain this game.
+    pub fn best(&self) -> usize {
+        self.best
+    }
+
+    pub fn multiplier(&self) -> usize {
+        (1 + self.kills / CHAIN_STEP).min(MAX_MULTIPLIER)
+    }
+
+    /// Fraction of [`CHAIN_SECS`] left to extend the chain.
+    pub fn remaining(&self) -> f32 {
+        if self.kills == 0 {
+            0.
+        } else {
+            self.timer.fraction_remaining()
+        }
+    }
+
+    fn extend(&mut self) {
+        self.kills += 1;
+        self.best = self.best.max(self.kills);
+        self.timer.reset();
+    }
+
+    fn reset(&mut self) {
+        self.kills = 0;
+    }
+}
+
+fn update_chain(
+    time: Res<Time>,
+    mut chain: ResMut<Chain>,
+    mut damage: EventReader<DamageDealt>,
+    mut bombs: EventReader<BombDetonated>,
+    player: Query<Entity, With<Player>>,
+) {
+    let hit = damage.read().any(|dealt| player.contains(dealt.entity));
+    let bombed = bombs.read().count() > 0;
+
+    if chain.kills > 0 && (hit || bombed || chain.timer.tick(time.delta()).finished()) {
+        chain.reset();
+    }
+}
+
 fn score_enemy_death(
     mut reader: EventReader<EnemyDeathEvent>,
     mut writer: EventWriter<PointEvent>,
+    mut chain: ResMut<Chain>,
+    player: Option<Single<&Transform, With<Player>>>,
 ) {
     for event in reader.read() {
-        writer.write(PointEvent {
-            points: 20,
-            position: event.position,
+        chain.extend();
+
+        let point_blank = player.as_ref().is_some_and(|player| {
+            player.translation.xy().distance(event.position) <= POINT_BLANK_RADIUS
         });
+        let base = if point_blank {
+            event.points * 2
+        } else {
+            event.points
+        };
+        writer.write(PointEvent::new(base, event.position).multiplied(chain.multiplier()));
     }
 }
 
@@ -68,11 +191,16 @@ fn point_effects(
     }
 
     for event in reader.read() {
-        points.0 += event.points;
+        points.0 += event.points();
+        let text = if event.multiplier > 1 {
+            format!("+{}x{}", event.base, event.multiplier)
+        } else {
+            format!("+{}", event.base)
+        };
         flash_text(
             &mut commands,
             &server,
-            format!("+{}", event.points),
+            text,
             20.,
             (event.position * RESOLUTION_SCALE).extend(POINT_TEXT_Z),
             COLOR,
@@ -0,0 +1,341 @@
+//! Input recording and deterministic replay.
+//!
+//! Set `CUCUMBER_RECORD=<path>` to record a run, or `CUCUMBER_REPLAY=<path>` to play one back.
+//!
+//! A replay stores the [`GameRng`] seed, the game version, and the state of every
+//! [`AliveContext`] action for each fixed tick in [`GameState::Game`]. Playback mocks the recorded
+//! actions, so they go through the same observers as live input.
+//!
+//! While recording or replaying, every frame advances time by exactly one fixed timestep. Frames
+//! where virtual time is slowed down, e.g. during a hit-stop, may not run a tick at all. Playback
+//! only moves on to the next recorded tick on frames that will run one, and holds the last one
+//! otherwise.
+use crate::{
+    GameState,
+    bomb::BombAction,
+    player::{AliveContext, FocusShot, MoveAction, NormalShot, Player},
+    rng::GameRng,
+};
+use bevy::{
+    prelude::*,
+    time::{TimeSystem, TimeUpdateStrategy},
+};
+use bevy_enhanced_input::prelude::*;
+use std::{
+    io::{self, Read, Write},
+    path::PathBuf,
+};
+
+const MAGIC: &[u8; 4] = b"CUCR";
+const FORMAT: u8 = 2;
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        let mode = if let Some(path) = std::env::var_os("CUCUMBER_REPLAY") {
+            match Replay::load(&PathBuf::from(&path)) {
+                Ok(replay) => {
+                    if replay.version != env!("CARGO_PKG_VERSION") {
+                        warn!(
+                            "replay was recorded with version {}, this is {}",
+                            replay.version,
+                            env!("CARGO_PKG_VERSION")
+                        );
+                    }
+                    ReplayMode::Playback {
+                        frames: Box::new(replay.frames()),
+                        seed: replay.seed,
+                    }
+                }
+                Err(err) => {
+                    error!("could not load replay {path:?}: {err}");
+                    return;
+                }
+            }
+        } else if let Some(path) = std::env::var_os("CUCUMBER_RECORD") {
+            ReplayMode::Record {
+                path: path.into(),
+                replay: None,
+            }
+        } else {
+            return;
+        };
+
+        app.insert_resource(mode)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(
+                Time::<Fixed>::default().timestep(),
+            ))
+            .add_systems(Startup, seed_playback)
+            .add_systems(OnEnter(GameState::StartGame), begin)
+            .add_systems(OnEnter(GameState::Restart), finish)
+            .add_systems(First, play_back.after(TimeSystem))
+            .add_systems(FixedPreUpdate, record.run_if(in_state(GameState::Game)))
+            .add_systems(Last, finish_on_exit);
+    }
+}
+
+/// The state of every [`AliveContext`] action for one fixed tick.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ReplayFrame {
+    pub movement: Vec2,
+    pub normal_shot: ActionState,
+    pub focus_shot: ActionState,
+    pub bomb: ActionState,
+}
+
+impl ReplayFrame {
+    fn from_actions(actions: &Actions<AliveContext>) -> Self {
+        Self {
+            movement: actions.action::<MoveAction>().value().as_axis2d(),
+            normal_shot: actions.action::<NormalShot>().state(),
+            focus_shot: actions.action::<FocusShot>().state(),
+            bomb: actions.action::<BombAction>().state(),
+        }
+    }
+
+    pub(crate) fn mock(&self, actions: &mut Actions<AliveContext>) {
+        let held = |state: ActionState| state != ActionState::None;
+        let movement = if self.movement != Vec2::ZERO {
+            ActionState::Fired
+        } else {
+            ActionState::None
+        };
+
+        actions.mock_once::<MoveAction>(movement, self.movement);
+        actions.mock_once::<NormalShot>(self.normal_shot, held(self.normal_shot));
+        actions.mock_once::<FocusShot>(self.focus_shot, held(self.focus_shot));
+        actions.mock_once::<BombAction>(self.bomb, held(self.bomb));
+    }
+
+    /// Two bits per button.
+    fn buttons(&self) -> u8 {
+        state_bits(self.normal_shot) | state_bits(self.focus_shot) << 2 | state_bits(self.bomb) << 4
+    }
+
+    fn from_buttons(movement: Vec2, buttons: u8) -> Self {
+        Self {
+            movement,
+            normal_shot: bits_state(buttons),
+            focus_shot: bits_state(buttons >> 2),
+            bomb: bits_state(buttons >> 4),
+        }
+    }
+}
+
+fn state_bits(state: ActionState) -> u8 {
+    match state {
+        ActionState::None => 0,
+        ActionState::Ongoing => 1,
+        ActionState::Fired => 2,
+    }
+}
+
+fn bits_state(bits: u8) -> ActionState {
+    match bits & 0b11 {
+        1 => ActionState::Ongoing,
+        2 => ActionState::Fired,
+        _ => ActionState::None,
+    }
+}
+
+/// A recorded run. Frames are run-length encoded, since inputs rarely change between frames.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    pub version: String,
+    pub seed: u64,
+    runs: Vec<(u32, ReplayFrame)>,
+}
+
+impl Replay {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            seed,
+            runs: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, frame: ReplayFrame) {
+        match self.runs.last_mut() {
+            Some((count, last)) if *last == frame => *count += 1,
+            _ => self.runs.push((1, frame)),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.runs.iter().map(|(count, _)| *count as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    pub fn frames(&self) -> impl Iterator<Item = ReplayFrame> + Send + Sync + 'static {
+        self.runs
+            .clone()
+            .into_iter()
+            .flat_map(|(count, frame)| std::iter::repeat_n(frame, count as usize))
+    }
+
+    pub fn save(&self, path: &std::path::Path) -> io::Result<()> {
+        let mut file = io::BufWriter::new(std::fs::File::create(path)?);
+        file.write_all(MAGIC)?;
+        file.write_all(&[FORMAT])?;
+        file.write_all(&(self.version.len() as u16).to_le_bytes())?;
+        file.write_all(self.version.as_bytes())?;
+        file.write_all(&self.seed.to_le_bytes())?;
+        file.write_all(&(self.runs.len() as u32).to_le_bytes())?;
+        for (count, frame) in self.runs.iter() {
+            file.write_all(&count.to_le_bytes())?;
+            file.write_all(&[frame.buttons()])?;
+            file.write_all(&frame.movement.x.to_le_bytes())?;
+            file.write_all(&frame.movement.y.to_le_bytes())?;
+        }
+        file.flush()
+    }
+
+    pub fn load(path: &std::path::Path) -> io::Result<Self> {
+        let mut file = io::BufReader::new(std::fs::File::open(path)?);
+
+        let mut magic = [0; 4];
+        file.read_exact(&mut magic)?;
+        let [format] = read::<1>(&mut file)?;
+        if &magic != MAGIC || format != FORMAT {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a replay, or an unsupported format",
+            ));
+        }
+
+        let len = u16::from_le_bytes(read(&mut file)?);
+        let mut version = vec![0; len as usize];
+        file.read_exact(&mut version)?;
+        let version = String::from_utf8(version)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        let seed = u64::from_le_bytes(read(&mut file)?);
+        let len = u32::from_le_bytes(read(&mut file)?);
+        let mut runs = Vec::with_capacity(len as usize);
+        for _ in 0..len {
+            let count = u32::from_le_bytes(read(&mut file)?);
+            let [buttons] = read::<1>(&mut file)?;
+            let x = f32::from_le_bytes(read(&mut file)?);
+            let y = f32::from_le_bytes(read(&mut file)?);
+            runs.push((count, ReplayFrame::from_buttons(Vec2::new(x, y), buttons)));
+        }
+
+        Ok(Self {
+            version,
+            seed,
+            runs,
+        })
+    }
+}
+
+fn read<const N: usize>(reader: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+#[derive(Resource)]
+enum ReplayMode {
+    Record {
+        path: PathBuf,
+        replay: Option<Replay>,
+    },
+    Playback {
+        frames: Box<dyn Iterator<Item = ReplayFrame> + Send + Sync>,
+        seed: u64,
+    },
+    /// Playback ran out of frames, live input takes over.
+    Finished,
+}
+
+fn seed_playback(mode: Res<ReplayMode>, mut rng: ResMut<GameRng>) {
+    if let ReplayMode::Playback { seed, .. } = *mode {
+        rng.pin(seed);
+    }
+}
+
+fn begin(mut mode: ResMut<ReplayMode>, rng: Res<GameRng>) {
+    if let ReplayMode::Record { replay, .. } = &mut *mode {
+        *replay = Some(Replay::new(rng.seed()));
+    }
+}
+
+fn record(
+    mut mode: ResMut<ReplayMode>,
+    player: Option<Single<&Actions<AliveContext>, With<Player>>>,
+) {
+    let ReplayMode::Record {
+        replay: Some(replay),
+        ..
+    } = &mut *mode
+    else {
+        return;
+    };
+    let Some(actions) = player else {
+        return;
+    };
+
+    replay.push(ReplayFrame::from_actions(&actions));
+}
+
+/// Outside of [`GameState::Game`], both modes hold the player's actions neutral so that the first
+/// recorded tick lines up with the first replayed one.
+fn play_back(
+    mut mode: ResMut<ReplayMode>,
+    state: Res<State<GameState>>,
+    player: Option<Single<&mut Actions<AliveContext>, With<Player>>>,
+    fixed: Res<Time<Fixed>>,
+    time: Res<Time<Virtual>>,
+    mut last: Local<ReplayFrame>,
+) {
+    let Some(mut actions) = player else {
+        return;
+    };
+
+    match (&mut *mode, state.get()) {
+        (ReplayMode::Playback { frames, .. }, GameState::Game) => {
+            // the fixed main loop runs after input is read, so this frame's ticks are known here
+            let ticks = (fixed.overstep() + time.delta()).as_nanos() / fixed.timestep().as_nanos();
+            for _ in 0..ticks {
+                match frames.next() {
+                    Some(frame) => *last = frame,
+                    None => {
+                        info!("replay finished");
+                        *mode = ReplayMode::Finished;
+                        return;
+                    }
+                }
+            }
+        }
+        (ReplayMode::Finished, _) | (ReplayMode::Record { .. }, GameState::Game) => return,
+        (ReplayMode::Playback { .. } | ReplayMode::Record { .. }, _) => {
+            *last = ReplayFrame::default();
+        }
+    }
+
+    last.mock(&mut actions);
+}
+
+fn finish(mut mode: ResMut<ReplayMode>) {
+    let ReplayMode::Record { path, replay } = &mut *mode else {
+        return;
+    };
+
+    if let Some(replay) = replay.take() {
+        match replay.save(path) {
+            Ok(()) => info!("saved {} frame replay to {path:?}", replay.len()),
+            Err(err) => error!("could not save replay to {path:?}: {err}"),
+        }
+    }
+}
+
+fn finish_on_exit(mode: ResMut<ReplayMode>, mut reader: EventReader<AppExit>) {
+    if reader.read().next().is_some() {
+        finish(mode);
+    }
+}
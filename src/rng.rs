@@ -0,0 +1,104 @@
+use crate::GameState;
+use bevy::{platform::collections::HashMap, prelude::*};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+pub struct RngPlugin;
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        let rng = match std::env::var("CUCUMBER_SEED")
+            .ok()
+            .and_then(|seed| seed.parse().ok())
+        {
+            Some(seed) => GameRng::pinned(seed),
+            None => GameRng::new(rand::random()),
+        };
+
+        app.insert_resource(rng)
+            .add_systems(OnEnter(GameState::StartGame), reset_rng)
+            .add_systems(OnEnter(GameState::Restart), reseed_rng);
+    }
+}
+
+/// Independent random streams, forked from the [`GameRng`] seed.
+///
+/// Each subsystem draws from its own stream so that, for example, an extra drop roll does not
+/// shift the asteroid spawns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RngStream {
+    Bullets,
+    Homing,
+    Drops,
+    Enemies,
+    Asteroids,
+    Background,
+    Pickups,
+    Audio,
+}
+
+/// The source of all gameplay randomness.
+///
+/// Given the same seed and inputs, a run plays out identically. Set `CUCUMBER_SEED` to play every
+/// run with a specific seed. Otherwise, restarting rolls a new one.
+#[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    pinned: bool,
+    streams: HashMap<RngStream, ChaCha8Rng>,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            pinned: false,
+            streams: HashMap::default(),
+        }
+    }
+
+    /// Like [`GameRng::new`], but restarts keep the seed.
+    pub fn pinned(seed: u64) -> Self {
+        Self {
+            pinned: true,
+            ..Self::new(seed)
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Start over from `seed`, dropping every forked stream.
+    pub fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.streams.clear();
+    }
+
+    /// Start over from `seed`, and keep it for every run after this one.
+    pub fn pin(&mut self, seed: u64) {
+        self.pinned = true;
+        self.reseed(seed);
+    }
+
+    pub fn stream(&mut self, stream: RngStream) -> &mut ChaCha8Rng {
+        let seed = self.seed;
+        self.streams.entry(stream).or_insert_with(|| {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            rng.set_stream(stream as u64);
+            rng
+        })
+    }
+}
+
+fn reset_rng(mut rng: ResMut<GameRng>) {
+    let seed = rng.seed();
+    rng.reseed(seed);
+    info!("game seed: {seed}");
+}
+
+fn reseed_rng(mut rng: ResMut<GameRng>) {
+    if !rng.pinned {
+        rng.reseed(rand::random());
+    }
+}
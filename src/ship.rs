@@ -0,0 +1,242 @@
+use crate::{
+    GameState, RESOLUTION_SCALE,
+    assets::RonAssetAppExt,
+    bomb::BombKind,
+    hitbox::Hitbox,
+    input::{Interact, Left, Right},
+    sprites::{self, CellSize},
+};
+use bevy::prelude::*;
+use bevy_enhanced_input::prelude::*;
+use bevy_optix::pixel_perfect::HIGH_RES_LAYER;
+use serde::Deserialize;
+
+const SHIPS: &[&str] = &["ships/striker.ship.ron", "ships/lancer.ship.ron"];
+
+pub struct ShipPlugin;
+
+impl Plugin for ShipPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_ron_asset::<ShipDefinition>(&["ship.ron"])
+            .add_systems(Startup, load_ships)
+            .add_systems(OnEnter(GameState::ShipSelect), spawn_select_screen)
+            .add_systems(OnExit(GameState::ShipSelect), despawn_select_screen)
+            .add_systems(
+                Update,
+                update_select_screen.run_if(in_state(GameState::ShipSelect)),
+            )
+            .add_observer(select_left)
+            .add_observer(select_right)
+            .add_observer(confirm);
+    }
+}
+
+/// Everything that sets one player ship apart from another.
+#[derive(Debug, Clone, Asset, TypePath, Deserialize)]
+pub struct ShipDefinition {
+    pub name: String,
+    pub speed: f32,
+    pub focus_speed: f32,
+    pub hitbox: Hitbox,
+    pub normal: ShotTable,
+    pub focus: ShotTable,
+    pub bomb: BombKind,
+    pub sprite: ShipSprite,
+}
+
+/// Shot levels, ordered by the power they unlock at.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(transparent)]
+pub struct ShotTable(pub Vec<ShotLevel>);
+
+impl ShotTable {
+    /// The highest level `power` has reached.
+    pub fn level(&self, power: f32) -> Option<&ShotLevel> {
+        self.0.iter().rev().find(|level| power >= level.power)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ShotLevel {
+    /// Power needed to reach this level.
+    pub power: f32,
+    /// Overrides the emitter's wait between pulses.
+    #[serde(default)]
+    pub wait: Option<f32>,
+    pub shots: Vec<Shot>,
+}
+
+/// A single bullet fired from the emitter.
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct Shot {
+    pub offset: Vec2,
+    /// Radians clockwise from straight up.
+    pub angle: f32,
+}
+
+/// Cells in `ships.png` for the ship's [`TiltSprite`](crate::sprites::TiltSprite).
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct ShipSprite {
+    pub left: UVec2,
+    pub center: UVec2,
+    pub right: UVec2,
+}
+
+/// The definition the player was spawned from.
+#[derive(Component)]
+pub struct Ship(pub ShipDefinition);
+
+/// Every selectable ship, in the order they are shown on the select screen.
+#[derive(Resource)]
+pub struct Ships {
+    ships: Vec<Handle<ShipDefinition>>,
+    selected: usize,
+}
+
+impl Ships {
+    pub fn selected<'a>(&self, assets: &'a Assets<ShipDefinition>) -> Option<&'a ShipDefinition> {
+        assets.get(&self.ships[self.selected])
+    }
+
+    pub fn select(&mut self, index: usize) {
+        self.selected = index % self.ships.len();
+    }
+
+    pub fn len(&self) -> usize {
+        self.ships.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ships.is_empty()
+    }
+}
+
+fn load_ships(mut commands: Commands, server: Res<AssetServer>) {
+    commands.insert_resource(Ships {
+        ships: SHIPS.iter().map(|path| server.load(*path)).collect(),
+        selected: 0,
+    });
+}
+
+#[derive(Component)]
+struct SelectScreen;
+
+#[derive(Component)]
+struct ShipName;
+
+#[derive(Component)]
+struct ShipStats;
+
+#[derive(Component)]
+struct ShipPreview;
+
+fn spawn_select_screen(mut commands: Commands, server: Res<AssetServer>) {
+    let font = TextFont {
+        font_size: 32.,
+        font: server.load("fonts/gravity.ttf"),
+        ..Default::default()
+    };
+
+    commands.spawn((
+        SelectScreen,
+        HIGH_RES_LAYER,
+        Text2d::new("SELECT SHIP"),
+        font.clone(),
+        Transform::from_xyz(0., crate::HEIGHT / 4. * RESOLUTION_SCALE, 500.),
+    ));
+    commands.spawn((
+        SelectScreen,
+        ShipName,
+        HIGH_RES_LAYER,
+        Text2d::default(),
+        font.clone(),
+        Transform::from_xyz(0., -16. * RESOLUTION_SCALE, 500.),
+    ));
+    commands.spawn((
+        SelectScreen,
+        ShipStats,
+        HIGH_RES_LAYER,
+        Text2d::default(),
+        TextFont {
+            font_size: 16.,
+            ..font
+        },
+        Transform::from_xyz(0., -32. * RESOLUTION_SCALE, 500.),
+    ));
+    commands.spawn((
+        SelectScreen,
+        ShipPreview,
+        Transform::from_scale(Vec3::splat(2.)),
+    ));
+}
+
+fn despawn_select_screen(mut commands: Commands, screen: Query<Entity, With<SelectScreen>>) {
+    for entity in screen.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn update_select_screen(
+    mut commands: Commands,
+    server: Res<AssetServer>,
+    ships: Res<Ships>,
+    definitions: Res<Assets<ShipDefinition>>,
+    mut name: Single<&mut Text2d, With<ShipName>>,
+    mut stats: Single<&mut Text2d, (With<ShipStats>, Without<ShipName>)>,
+    preview: Single<Entity, With<ShipPreview>>,
+    mut shown: Local<Option<AssetId<ShipDefinition>>>,
+) {
+    let id = ships.ships[ships.selected].id();
+    if *shown == Some(id) {
+        return;
+    }
+    let Some(ship) = definitions.get(id) else {
+        return;
+    };
+    *shown = Some(id);
+
+    name.0 = format!("< {} >", ship.name);
+    stats.0 = format!(
+        "speed {:.0}  focus {:.0}\ngraze {:.0}  bomb {}",
+        ship.speed,
+        ship.focus_speed,
+        ship.hitbox.graze_radius,
+        match ship.bomb {
+            BombKind::Clear => "clear",
+            BombKind::Burst { .. } => "burst",
+        }
+    );
+    commands.entity(*preview).insert(sprites::sprite_rect(
+        &server,
+        "ships.png",
+        CellSize::Eight,
+        ship.sprite.center,
+    ));
+}
+
+fn select_left(_: Trigger<Fired<Left>>, state: Res<State<GameState>>, mut ships: ResMut<Ships>) {
+    if *state.get() == GameState::ShipSelect {
+        let index = ships.selected + ships.len() - 1;
+        ships.select(index);
+    }
+}
+
+fn select_right(_: Trigger<Fired<Right>>, state: Res<State<GameState>>, mut ships: ResMut<Ships>) {
+    if *state.get() == GameState::ShipSelect {
+        let index = ships.selected + 1;
+        ships.select(index);
+    }
+}
+
+fn confirm(
+    _: Trigger<Fired<Interact>>,
+    mut commands: Commands,
+    state: Res<State<GameState>>,
+    ships: Res<Ships>,
+    definitions: Res<Assets<ShipDefinition>>,
+) {
+    if *state.get() == GameState::ShipSelect && ships.selected(&definitions).is_some() {
+        commands.set_state(GameState::StartGame);
+    }
+}
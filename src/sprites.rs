@@ -4,6 +4,7 @@ use avian2d::prelude::*;
 use bevy::prelude::*;
 use bevy_tween::interpolate::rotation;
 use bevy_tween::prelude::*;
+use serde::Deserialize;
 use std::f32::EPSILON;
 use std::f32::consts::PI;
 
@@ -102,7 +103,7 @@ impl CellSprite {
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, Deserialize)]
 pub enum CellSize {
     Eight,
     Sixteen,
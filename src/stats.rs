@@ -1,6 +1,7 @@
 use crate::GameState;
 use crate::enemy::EnemyDeathEvent;
 use crate::pickups::{Material, PickupEvent};
+use crate::points::PointEvent;
 use bevy::prelude::*;
 use bevy::time::Stopwatch;
 
@@ -23,6 +24,10 @@ pub struct Stats {
     pub time: GameTime,
     pub kills: usize,
     pub materials: usize,
+    /// Points scored before multipliers.
+    pub base_points: usize,
+    /// Points added by the chain multiplier.
+    pub chain_points: usize,
 }
 
 pub struct GameTime {
@@ -51,6 +56,7 @@ fn track_stats(
     mut stats: ResMut<Stats>,
     mut kills: EventReader<EnemyDeathEvent>,
     mut materials: EventReader<PickupEvent>,
+    mut points: EventReader<PointEvent>,
     time: Res<Time>,
 ) {
     stats.time.tick(&time);
@@ -61,4 +67,9 @@ fn track_stats(
             |pickup| matches!(pickup, PickupEvent::Material(mat) if matches!(mat, Material::Parts)),
         )
         .count();
+
+    for event in points.read() {
+        stats.base_points += event.base;
+        stats.chain_points += event.points() - event.base;
+    }
 }
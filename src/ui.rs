@@ -1,8 +1,10 @@
 use crate::assets::{PROJECTILES_COLORED_PATH, SHIPS_PATH};
 use crate::bomb::Bombs;
-use crate::health::Health;
-use crate::player::Player;
-use crate::points::{self, Points};
+use crate::health::Shield;
+use crate::hitbox::GrazeMeter;
+use crate::lives::Lives;
+use crate::player::{PLAYER_SHIELD, Player, Power};
+use crate::points::{self, Chain, Points};
 use crate::sprites::CellSize;
 use crate::text::TextFlash;
 use crate::{DespawnRestart, GameState, sprites};
@@ -17,7 +19,7 @@ impl Plugin for UiPlugin {
         app.insert_resource(PointAccumulator(0))
             .add_systems(OnEnter(GameState::Restart), restart)
             .add_systems(OnEnter(GameState::StartGame), ui)
-            .add_systems(Update, update_ui)
+            .add_systems(Update, (update_ui, update_shield_hud, update_chain_text))
             .add_systems(FixedUpdate, accumulate_points);
     }
 }
@@ -35,6 +37,28 @@ struct BombText;
 #[derive(Component)]
 struct GamePointText;
 
+#[derive(Component)]
+struct PowerText;
+
+#[derive(Component)]
+struct ChainText;
+
+#[derive(Component)]
+struct GrazeBar;
+
+const GRAZE_BAR_WIDTH: f32 = 30.;
+
+/// One charge of the player's [`Shield`], filling up as it recharges.
+#[derive(Component)]
+struct ShieldIcon(usize);
+
+#[derive(Component)]
+struct ShieldFill;
+
+const SHIELD_UI_PATH: &str = "shield_ui.png";
+/// Distance between shield icons, which are drawn at half size.
+const SHIELD_ICON_SPACING: f32 = 9.;
+
 fn ui(mut commands: Commands, server: Res<AssetServer>) {
     let mut lives_sprite =
         sprites::sprite_rect(&server, SHIPS_PATH, CellSize::Eight, UVec2::new(1, 5));
@@ -94,6 +118,92 @@ fn ui(mut commands: Commands, server: Res<AssetServer>) {
                 .with_scale(Vec3::splat(crate::RESOLUTION_SCALE)),
         ));
 
+    commands.spawn((
+        DespawnRestart,
+        PowerText,
+        HIGH_RES_LAYER,
+        Text2d::default(),
+        TextFont {
+            font_size: 16.,
+            font: server.load("fonts/gravity.ttf"),
+            ..Default::default()
+        },
+        Transform::from_xyz(
+            crate::WIDTH / 2. * crate::RESOLUTION_SCALE - 2. * crate::RESOLUTION_SCALE,
+            crate::HEIGHT / 2. * crate::RESOLUTION_SCALE - 12. * crate::RESOLUTION_SCALE,
+            500.,
+        ),
+        Anchor::TopRight,
+    ));
+
+    commands.spawn((
+        DespawnRestart,
+        GrazeBar,
+        HIGH_RES_LAYER,
+        Sprite {
+            anchor: Anchor::CenterLeft,
+            ..Sprite::from_color(
+                points::COLOR,
+                Vec2::new(GRAZE_BAR_WIDTH, 1.) * crate::RESOLUTION_SCALE,
+            )
+        },
+        Transform::from_xyz(
+            -crate::WIDTH / 2. * crate::RESOLUTION_SCALE + 2. * crate::RESOLUTION_SCALE,
+            crate::HEIGHT / 2. * crate::RESOLUTION_SCALE - 12. * crate::RESOLUTION_SCALE,
+            500.,
+        )
+        .with_scale(Vec3::new(0., 1., 1.)),
+    ));
+
+    for i in 0..PLAYER_SHIELD as usize {
+        let mut empty =
+            sprites::sprite_rect(&server, SHIELD_UI_PATH, CellSize::Sixteen, UVec2::new(1, 0));
+        empty.anchor = Anchor::TopLeft;
+        let mut full =
+            sprites::sprite_rect(&server, SHIELD_UI_PATH, CellSize::Sixteen, UVec2::new(0, 0));
+        full.anchor = Anchor::TopLeft;
+
+        commands
+            .spawn((
+                DespawnRestart,
+                ShieldIcon(i),
+                HIGH_RES_LAYER,
+                empty,
+                Transform::from_xyz(
+                    -crate::WIDTH / 2. * crate::RESOLUTION_SCALE
+                        + (2. + i as f32 * SHIELD_ICON_SPACING) * crate::RESOLUTION_SCALE,
+                    crate::HEIGHT / 2. * crate::RESOLUTION_SCALE - 14. * crate::RESOLUTION_SCALE,
+                    500.,
+                )
+                .with_scale(Vec3::splat(crate::RESOLUTION_SCALE / 2.)),
+            ))
+            .with_child((
+                ShieldFill,
+                HIGH_RES_LAYER,
+                full,
+                Transform::from_xyz(0., 0., 1.),
+            ));
+    }
+
+    commands.spawn((
+        DespawnRestart,
+        ChainText,
+        HIGH_RES_LAYER,
+        Text2d::default(),
+        TextFont {
+            font_size: 16.,
+            font: server.load("fonts/gravity.ttf"),
+            ..Default::default()
+        },
+        TextColor(points::COLOR.into()),
+        Transform::from_xyz(
+            crate::WIDTH / 2. * crate::RESOLUTION_SCALE - 2. * crate::RESOLUTION_SCALE,
+            crate::HEIGHT / 2. * crate::RESOLUTION_SCALE - 17. * crate::RESOLUTION_SCALE,
+            500.,
+        ),
+        Anchor::TopRight,
+    ));
+
     commands.spawn((
         DespawnRestart,
         GamePointText,
@@ -125,6 +235,8 @@ fn accumulate_points(
     let (text, point_text) = text.into_inner();
 
     let points = points.get();
+    // points are reset when continuing
+    accumulator.0 = accumulator.0.min(points);
     if accumulator.0 < points {
         accumulator.0 += 1;
         if point_text.is_none() {
@@ -144,12 +256,25 @@ fn update_ui(
         &mut Text2d,
         (With<GamePointText>, Without<LivesText>, Without<BombText>),
     >,
-    player: Single<Ref<Health>, With<Player>>,
+    mut power_text: Single<
+        &mut Text2d,
+        (
+            With<PowerText>,
+            Without<LivesText>,
+            Without<BombText>,
+            Without<GamePointText>,
+        ),
+    >,
+    mut graze_bar: Single<&mut Transform, With<GrazeBar>>,
     bombs: Res<Bombs>,
     points: Res<PointAccumulator>,
+    graze: Res<GrazeMeter>,
+    power: Res<Power>,
+    lives: Res<Lives>,
 ) {
-    if player.is_changed() {
-        live_text.0 = format!("{}", (player.current() - 1.).max(0.));
+    if lives.is_changed() {
+        // the ship in play is not counted
+        live_text.0 = format!("{}", lives.get().saturating_sub(1));
     }
 
     if bombs.is_changed() {
@@ -159,4 +284,48 @@ fn update_ui(
     if points.is_changed() {
         point_text.0 = format!("{}", points.0);
     }
+
+    if power.is_changed() {
+        power_text.0 = format!("{:.2}", power.get());
+    }
+
+    if graze.is_changed() {
+        graze_bar.scale.x = graze.fraction();
+    }
+}
+
+fn update_shield_hud(
+    player: Option<Single<&Shield, With<Player>>>,
+    icons: Query<(&ShieldIcon, &Children)>,
+    mut fills: Query<&mut Sprite, With<ShieldFill>>,
+) {
+    let Some(shield) = player else {
+        return;
+    };
+
+    for (icon, children) in icons.iter() {
+        let charge = (shield.current() - icon.0 as f32).clamp(0., 1.);
+        let mut iter = fills.iter_many_mut(children.iter());
+        while let Some(mut sprite) = iter.fetch_next() {
+            // a charge only shows solid once it is complete
+            sprite.color = Color::WHITE.with_alpha(if charge < 1. { charge * 0.5 } else { 1. });
+        }
+    }
+}
+
+fn update_chain_text(
+    chain: Res<Chain>,
+    text: Single<(&mut Text2d, &mut TextColor), With<ChainText>>,
+) {
+    let (mut text, mut color) = text.into_inner();
+
+    if chain.is_changed() {
+        text.0 = if chain.kills() > 1 {
+            format!("{} CHAIN x{}", chain.kills(), chain.multiplier())
+        } else {
+            String::new()
+        };
+    }
+    // fades out as the chain is about to drop
+    color.0.set_alpha(0.4 + 0.6 * chain.remaining());
 }
@@ -0,0 +1,801 @@
+use avian2d::prelude::{Collider, ColliderDisabled, LinearVelocity};
+use bevy::prelude::*;
+use bevy_enhanced_input::prelude::ActionState;
+use cucumber::{
+    GameState,
+    boss::{
+        gradius::Gradius,
+        phases::{BossPhase, BossPhases},
+    },
+    bullet::{
+        Arrow, BlueOrb, PlayerBullet, RedOrb,
+        emitter::Target,
+        light::{LightBullet, LightBullets},
+        pattern::PatternEmitter,
+        script::ScriptEmitter,
+    },
+    campaign::{Campaign, StagePhase, StageTally},
+    enemy::{
+        Enemy, EnemyDeathEvent,
+        archetype::{ArchetypeTable, EnemyArchetype, EnemyArchetypes},
+        brain::{Behaviour, BrainState, EnemyBrain, Exit, Movement},
+        buckshot,
+        path::{Path, PathShape},
+        stage::{FormationRegistry, Stage},
+        timeline::WaveTimeline,
+        verger,
+    },
+    harness::TestGame,
+    health::{
+        Armour, DamageDealt, DamageEvent, DamageKind, Dead, Health, Invincible, NoShield,
+        Resistances, ShieldBroken,
+    },
+    hitbox::{GrazeMeter, Hitbox},
+    player::{PLAYER_HEALTH, PLAYER_SHIELD, Player, PowerUpEvent},
+    points::{CHAIN_STEP, Chain},
+    replay::{Replay, ReplayFrame},
+    rng::GameRng,
+    ship::Ship,
+};
+
+#[test]
+fn reaches_game() {
+    let mut game = TestGame::with_seed(1);
+    game.enter_game();
+
+    assert_eq!(game.state(), GameState::Game);
+    assert_eq!(game.points(), 0);
+    assert!(game.player_health().is_some());
+}
+
+#[test]
+fn waves_advance() {
+    let mut game = TestGame::with_seed(2);
+    game.enter_game();
+
+    let ticks = game.run_until(64 * 10, |world| {
+        world
+            .get_resource::<WaveTimeline>()
+            .is_some_and(|timeline| timeline.wave() > 1)
+    });
+    assert!(ticks.is_some(), "no waves spawned after 10 seconds");
+    assert!(game.stats().time.elapsed_secs() > 0.);
+}
+
+#[test]
+fn stage_intro_precedes_waves() {
+    let mut game = TestGame::with_seed(5);
+    game.enter_game();
+
+    assert_eq!(game.stage(), 0);
+    game.run_until(64 * 5, |world| {
+        *world.resource::<State<StagePhase>>().get() == StagePhase::Intro
+    })
+    .expect("the stage never loaded");
+    assert!(game.timeline().is_none());
+    game.tick();
+    let world = game.app().world_mut();
+    assert!(
+        world
+            .query::<&Text2d>()
+            .iter(world)
+            .any(|text| text.0 == "Stage 1"),
+        "no intro card"
+    );
+
+    let ticks = game.run_until(64 * 5, |world| {
+        *world.resource::<State<StagePhase>>().get() == StagePhase::Playing
+    });
+    assert!(ticks.is_some(), "the stage intro never ended");
+}
+
+#[test]
+fn bullet_hits_count_as_misses() {
+    let mut game = TestGame::with_seed(32);
+    game.enter_game();
+    game.run_until(64 * 10, |world| {
+        *world.resource::<State<StagePhase>>().get() == StagePhase::Playing
+    })
+    .expect("the stage never started");
+    assert_eq!(game.world().resource::<StageTally>().misses(), 0);
+
+    let player = game.player();
+    let translation = game.world().get::<Transform>(player).unwrap().translation;
+    game.app()
+        .world_mut()
+        .spawn((RedOrb, Transform::from_translation(translation)));
+    game.run_until(8, |world| world.resource::<StageTally>().misses() > 0)
+        .expect("the hit was not counted as a miss");
+}
+
+#[test]
+fn held_input_moves_player() {
+    let mut game = TestGame::with_seed(3);
+    game.enter_game();
+
+    let start = player_x(&mut game);
+    game.hold(ReplayFrame {
+        movement: Vec2::X,
+        ..Default::default()
+    })
+    .ticks(32);
+
+    assert!(player_x(&mut game) > start);
+}
+
+#[test]
+fn same_seed_same_run() {
+    let run = |seed| {
+        let mut game = TestGame::with_seed(seed);
+        game.enter_game()
+            .hold(ReplayFrame {
+                normal_shot: ActionState::Fired,
+                ..Default::default()
+            })
+            .ticks(64 * 20);
+        (game.points(), game.stats().kills)
+    };
+
+    assert_eq!(run(4), run(4));
+}
+
+#[test]
+fn ships_bring_their_own_hitbox() {
+    for (index, name, graze_radius) in [(0, "Striker", 15.), (1, "Lancer", 10.)] {
+        let mut game = TestGame::with_seed(16);
+        game.select_ship(index).enter_game();
+
+        let player = game.player();
+        let world = game.world();
+        assert_eq!(world.get::<Ship>(player).unwrap().0.name, name);
+        let hitbox = world.get::<Hitbox>(player).unwrap();
+        assert_eq!(hitbox.graze_radius, graze_radius);
+        assert!(world.get::<Collider>(player).is_some());
+    }
+}
+
+#[test]
+fn power_selects_the_shot_level() {
+    let mut game = TestGame::with_seed(17);
+    game.select_ship(0).enter_game();
+    assert_eq!(game.power(), 0.);
+
+    let bullets = |world: &World| {
+        world
+            .iter_entities()
+            .filter(|entity| entity.contains::<PlayerBullet>())
+            .count()
+    };
+    let volley = |game: &mut TestGame| {
+        game.hold(ReplayFrame {
+            normal_shot: ActionState::Fired,
+            ..Default::default()
+        });
+        game.run_until(64, |world| bullets(world) > 0)
+            .expect("the player never fired");
+        let fired = bullets(game.world());
+        game.release();
+        game.run_until(64 * 3, |world| bullets(world) == 0)
+            .expect("the bullets never left");
+        fired
+    };
+
+    // the striker's first two levels
+    assert_eq!(volley(&mut game), 2);
+    game.app().world_mut().send_event(PowerUpEvent(2.));
+    game.tick();
+    assert_eq!(game.power(), 2.);
+    assert_eq!(volley(&mut game), 6);
+}
+
+#[test]
+fn stage_timeline_comes_from_the_stage_file() {
+    let mut game = TestGame::with_seed(18);
+    game.enter_game();
+    game.run_until(64 * 5, |world| world.contains_resource::<WaveTimeline>())
+        .expect("the stage never started");
+
+    let world = game.world();
+    let stage = world
+        .resource::<Campaign>()
+        .stage(world.resource::<Assets<Stage>>())
+        .expect("stage is loaded");
+    assert_eq!(stage.name, "Outer Rim");
+    let expected = stage
+        .timeline(world.resource::<FormationRegistry>())
+        .waves();
+    assert!(expected > 1);
+    assert_eq!(game.timeline().unwrap().waves(), expected);
+}
+
+#[test]
+fn paths_carry_enemies_to_their_end() {
+    let mut game = TestGame::with_seed(19);
+    game.enter_game();
+
+    let end = Vec2::new(30., -20.);
+    let curve = PathShape::catmull_rom([Vec2::new(-30., 20.), Vec2::ZERO, end]).build();
+    let enemy = game
+        .app()
+        .world_mut()
+        .spawn((Transform::default(), Path::new(curve, 120.).stopping()))
+        .id();
+
+    let ticks = game.run_until(64 * 2, |world| !world.entity(enemy).contains::<Path>());
+    assert!(ticks.is_some(), "the path never ended");
+    let world = game.world();
+    let position = world.get::<Transform>(enemy).unwrap().translation.xy();
+    assert!(position.distance(end) < 1., "ended at {position}");
+    assert_eq!(world.get::<LinearVelocity>(enemy).unwrap().0, Vec2::ZERO);
+}
+
+#[test]
+fn brains_follow_their_exits() {
+    let mut game = TestGame::with_seed(20);
+    game.enter_game();
+
+    let brain = EnemyBrain::new([
+        (
+            BrainState::Enter,
+            Behaviour::new().exit(Exit::After(0.25), BrainState::Attack),
+        ),
+        (
+            BrainState::Attack,
+            Behaviour::new()
+                .movement(Movement::Hold)
+                .exit(Exit::Health(0.5), BrainState::Retreat),
+        ),
+        (
+            BrainState::Retreat,
+            Behaviour::new().movement(Movement::Velocity(Vec2::Y * 40.)),
+        ),
+    ]);
+    let enemy = game
+        .app()
+        .world_mut()
+        .spawn((Transform::default(), Health::full(10.), brain))
+        .id();
+    let state = |game: &TestGame| game.world().get::<EnemyBrain>(enemy).unwrap().state();
+
+    game.ticks(8);
+    assert_eq!(state(&game), BrainState::Enter);
+    game.ticks(16);
+    assert_eq!(state(&game), BrainState::Attack);
+    assert_eq!(
+        game.world().get::<LinearVelocity>(enemy).unwrap().0,
+        Vec2::ZERO
+    );
+
+    game.app()
+        .world_mut()
+        .send_event(DamageEvent::new(enemy, 6.));
+    game.ticks(2);
+    assert_eq!(state(&game), BrainState::Retreat);
+    assert_eq!(
+        game.world().get::<LinearVelocity>(enemy).unwrap().0,
+        Vec2::Y * 40.
+    );
+}
+
+#[test]
+fn boss_phases_advance_with_health() {
+    let mut game = TestGame::with_seed(22);
+    game.enter_game();
+
+    let boss = game.app().world_mut().spawn(Gradius).id();
+    game.ticks(2);
+    let phase = |game: &TestGame| game.world().get::<BossPhases>(boss).unwrap().current();
+    assert_eq!(phase(&game), 0);
+    assert!(
+        game.world()
+            .iter_entities()
+            .filter_map(|entity| entity.get::<Text2d>())
+            .any(|text| text.0 == "Gradius"),
+        "the boss has no health bar"
+    );
+
+    game.app()
+        .world_mut()
+        .send_event(DamageEvent::new(boss, 101.));
+    game.ticks(2);
+    assert_eq!(phase(&game), 1);
+    assert!(
+        game.world()
+            .get::<BossPhases>(boss)
+            .unwrap()
+            .time_left()
+            .is_some()
+    );
+}
+
+#[test]
+fn timed_out_last_phase_keeps_the_boss_alive() {
+    let mut game = TestGame::with_seed(25);
+    game.enter_game();
+
+    let boss = game
+        .app()
+        .world_mut()
+        .spawn((
+            Transform::default(),
+            Health::full(10.),
+            BossPhases::new([BossPhase::until_health(0.).time_limit(0.25)]),
+        ))
+        .id();
+    game.ticks(32);
+
+    let world = game.world();
+    assert_eq!(world.get::<BossPhases>(boss).unwrap().time_left(), Some(0.));
+    assert_eq!(world.get::<Health>(boss).unwrap().current(), 10.);
+    assert!(world.get::<Dead>(boss).is_none());
+}
+
+#[test]
+fn archetypes_apply_their_stats() {
+    let mut game = TestGame::with_seed(21);
+    game.enter_game();
+    game.run_until(64, |world| {
+        world
+            .resource::<EnemyArchetypes>()
+            .is_loaded(world.resource::<Assets<ArchetypeTable>>())
+    })
+    .expect("the archetype table never loaded");
+
+    let enemy = game
+        .app()
+        .world_mut()
+        .spawn((Transform::default(), EnemyArchetype("buckshot")))
+        .id();
+    game.tick();
+
+    let world = game.world();
+    assert_eq!(world.get::<Health>(enemy).unwrap().max(), 6.);
+    assert!(world.get::<Collider>(enemy).is_some());
+
+    // unknown archetypes can still be destroyed
+    let unknown = game
+        .app()
+        .world_mut()
+        .spawn((Transform::default(), EnemyArchetype("unknown")))
+        .id();
+    game.tick();
+    assert!(game.world().get::<Health>(unknown).is_some());
+}
+
+#[test]
+fn restart_keeps_a_pinned_seed() {
+    let mut game = TestGame::with_seed(14);
+    game.enter_game();
+    assert_eq!(game.world().resource::<GameRng>().seed(), 14);
+
+    game.app()
+        .world_mut()
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::Restart);
+    game.tick();
+    assert_ne!(game.state(), GameState::Game);
+    let ticks = game.run_until(64 * 5, |world| {
+        *world.resource::<State<GameState>>().get() == GameState::Game
+    });
+    assert!(ticks.is_some(), "the game did not restart");
+    assert_eq!(game.world().resource::<GameRng>().seed(), 14);
+}
+
+#[test]
+fn replays_round_trip_through_a_file() {
+    let mut replay = Replay::new(15);
+    let frames = [
+        ReplayFrame::default(),
+        ReplayFrame::default(),
+        ReplayFrame {
+            movement: Vec2::new(-1., 0.5),
+            normal_shot: ActionState::Fired,
+            ..Default::default()
+        },
+        ReplayFrame {
+            movement: Vec2::new(-1., 0.5),
+            normal_shot: ActionState::Fired,
+            ..Default::default()
+        },
+        ReplayFrame {
+            focus_shot: ActionState::Ongoing,
+            bomb: ActionState::Fired,
+            ..Default::default()
+        },
+    ];
+    for frame in frames {
+        replay.push(frame);
+    }
+    assert_eq!(replay.len(), frames.len());
+
+    let path = std::env::temp_dir().join(format!("cucumber-{}.replay", std::process::id()));
+    replay.save(&path).expect("replay saved");
+    let loaded = Replay::load(&path).expect("replay loaded");
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(loaded.seed, 15);
+    assert_eq!(loaded.version, replay.version);
+    assert_eq!(loaded.len(), frames.len());
+    assert_eq!(loaded.frames().collect::<Vec<_>>(), frames);
+}
+
+#[test]
+fn death_takes_a_life_and_respawns() {
+    let mut game = TestGame::with_seed(6);
+    game.enter_game();
+    let lives = game.lives();
+
+    let player = game.player();
+    let world = game.app().world_mut();
+    world.entity_mut(player).insert(Dead);
+    game.tick();
+
+    assert_eq!(game.lives(), lives - 1);
+    assert!(game.player_health().is_none());
+
+    game.ticks(64 * 2);
+    let world = game.app().world_mut();
+    assert!(
+        world
+            .query_filtered::<(), (With<Player>, With<Invincible>)>()
+            .single(world)
+            .is_ok(),
+        "player did not respawn invincible"
+    );
+}
+
+#[test]
+fn a_hit_past_the_shield_costs_a_life() {
+    let mut game = TestGame::with_seed(26);
+    game.enter_game();
+    let lives = game.lives();
+
+    let player = game.player();
+    game.app()
+        .world_mut()
+        .send_event(DamageEvent::new(player, PLAYER_SHIELD + PLAYER_HEALTH));
+    game.ticks(2);
+
+    assert_eq!(game.lives(), lives - 1);
+}
+
+#[test]
+fn power_drops_on_death_not_on_hits() {
+    let mut game = TestGame::with_seed(24);
+    game.enter_game();
+    game.app().world_mut().send_event(PowerUpEvent(2.));
+    game.tick();
+
+    let player = game.player();
+    game.app()
+        .world_mut()
+        .send_event(DamageEvent::new(player, 1.));
+    game.tick();
+    assert_eq!(game.power(), 2.);
+
+    let player = game.player();
+    game.app().world_mut().entity_mut(player).insert(Dead);
+    game.tick();
+    assert_eq!(game.power(), 1.);
+}
+
+#[test]
+fn resistances_and_armour_mitigate_damage() {
+    let mut game = TestGame::with_seed(7);
+    game.enter_game();
+
+    let world = game.app().world_mut();
+    let target = world
+        .spawn((
+            Health::full(10.),
+            Resistances {
+                kinetic: 0.5,
+                ..Default::default()
+            },
+            Armour(1.),
+        ))
+        .id();
+    world.send_event(DamageEvent::new(target, 5.));
+    world.send_event(DamageEvent::new(target, 4.).kind(DamageKind::Energy));
+    game.tick();
+
+    let world = game.app().world_mut();
+    assert_eq!(world.get::<Health>(target).unwrap().current(), 4.5);
+    let dealt = world
+        .resource_mut::<Events<DamageDealt>>()
+        .drain()
+        .filter(|dealt| dealt.entity == target)
+        .map(|dealt| dealt.amount)
+        .collect::<Vec<_>>();
+    assert_eq!(dealt, [1.5, 4.]);
+}
+
+#[test]
+fn shield_breaks_and_recharges() {
+    let mut game = TestGame::with_seed(8);
+    game.enter_game();
+
+    let player = game.player();
+    let world = game.app().world_mut();
+    world.send_event(DamageEvent::new(player, PLAYER_SHIELD));
+    game.tick();
+
+    assert!(game.player_shield().unwrap().empty());
+    assert_eq!(game.player_health().unwrap().current(), PLAYER_HEALTH);
+    let world = game.app().world_mut();
+    assert!(world.entity(player).contains::<NoShield>());
+    assert!(world.entity(player).contains::<Invincible>());
+    assert!(
+        world
+            .resource_mut::<Events<ShieldBroken>>()
+            .drain()
+            .any(|broken| broken.entity == player)
+    );
+
+    game.ticks(64 * 6);
+    assert!(game.player_shield().unwrap().current() > 0.);
+    assert!(!game.world().entity(player).contains::<NoShield>());
+}
+
+#[test]
+fn player_hits_stop_time() {
+    let mut game = TestGame::with_seed(9);
+    game.enter_game();
+
+    let player = game.player();
+    let world = game.app().world_mut();
+    world.send_event(DamageEvent::new(player, 1.));
+    game.tick();
+    assert!(game.world().resource::<Time<Virtual>>().relative_speed() < 1.);
+
+    game.ticks(16);
+    assert_eq!(
+        game.world().resource::<Time<Virtual>>().relative_speed(),
+        1.
+    );
+}
+
+#[test]
+fn kill_chains_multiply_points_until_hit() {
+    let mut game = TestGame::with_seed(10);
+    game.enter_game();
+    let start = game.points();
+
+    let world = game.app().world_mut();
+    for _ in 0..CHAIN_STEP {
+        world.send_event(EnemyDeathEvent {
+            entity: Entity::PLACEHOLDER,
+            // far from the player, so not point-blank
+            position: Vec2::new(0., 1000.),
+            trauma: 0.,
+            points: 10,
+        });
+    }
+    game.tick();
+
+    let chain = game.world().resource::<Chain>();
+    assert_eq!(chain.kills(), CHAIN_STEP);
+    assert_eq!(chain.multiplier(), 2);
+    assert_eq!(game.points() - start, 10 * (CHAIN_STEP + 1));
+
+    game.tick();
+    assert_eq!(game.stats().chain_points, 10);
+
+    let player = game.player();
+    let world = game.app().world_mut();
+    world.send_event(DamageEvent::new(player, 1.));
+    game.tick();
+    assert_eq!(game.world().resource::<Chain>().kills(), 0);
+}
+
+#[test]
+fn verger_pattern_fires_rings() {
+    let mut game = TestGame::with_seed(11);
+    game.enter_game();
+
+    game.app().world_mut().spawn((
+        PatternEmitter::new(verger::PATTERN),
+        Transform::from_xyz(0., 40., 0.),
+    ));
+    let orbs = |world: &World| {
+        world
+            .iter_entities()
+            .filter(|entity| entity.contains::<RedOrb>())
+            .count()
+    };
+    let ticks = game.run_until(64 * 2, |world| orbs(world) > 0);
+    assert!(ticks.is_some(), "the pattern never fired");
+    assert_eq!(orbs(game.world()), 6);
+}
+
+#[test]
+fn buckshot_fans_orbs_from_its_pattern() {
+    let mut game = TestGame::with_seed(29);
+    game.enter_game();
+
+    game.app().world_mut().spawn((
+        PatternEmitter::new(buckshot::PATTERN),
+        Transform::from_xyz(0., 40., 0.),
+    ));
+    let orbs = |world: &World| {
+        world
+            .iter_entities()
+            .filter(|entity| entity.contains::<BlueOrb>())
+            .count()
+    };
+    let ticks = game.run_until(64 * 2, |world| orbs(world) > 0);
+    assert!(ticks.is_some(), "the pattern never fired");
+    assert_eq!(orbs(game.world()), 3);
+}
+
+#[test]
+fn emitter_aimed_patterns_keep_their_target() {
+    let mut game = TestGame::with_seed(30);
+    game.enter_game();
+
+    let emitter = game
+        .app()
+        .world_mut()
+        .spawn((
+            PatternEmitter::new("patterns/gradius_flank.pattern.ron"),
+            Target::dir(Vec2::X),
+            Transform::from_xyz(0., 40., 0.),
+        ))
+        .id();
+    let ticks = game.run_until(64 * 2, |world| {
+        world
+            .iter_entities()
+            .any(|entity| entity.contains::<BlueOrb>())
+    });
+    assert!(ticks.is_some(), "the pattern never fired");
+    let target = game.world().get::<Target>(emitter).unwrap();
+    assert_eq!(target.as_vec2(), Vec2::X);
+}
+
+#[test]
+fn flower_script_repeats_waits_and_fires() {
+    let mut game = TestGame::with_seed(12);
+    game.enter_game();
+
+    game.app().world_mut().spawn((
+        ScriptEmitter::new("patterns/gradius_flower.script.ron"),
+        Transform::from_xyz(0., 40., 0.),
+    ));
+    let count = |world: &World, bullet: fn(&EntityRef) -> bool| {
+        world
+            .iter_entities()
+            .filter(|entity| bullet(entity))
+            .count()
+    };
+    let orbs = |world: &World| count(world, |entity| entity.contains::<RedOrb>());
+    let arrows = |world: &World| count(world, |entity| entity.contains::<Arrow>());
+
+    let ticks = game.run_until(64 * 2, |world| orbs(world) > 0);
+    assert!(ticks.is_some(), "the script never fired");
+    // a ring is fired all at once, then the script waits
+    assert_eq!(orbs(game.world()), 10);
+    game.ticks(16);
+    assert_eq!(orbs(game.world()), 10);
+
+    // the next ring follows 0.4 seconds after the first
+    game.ticks(16);
+    assert_eq!(orbs(game.world()), 20);
+    assert_eq!(arrows(game.world()), 0);
+
+    // the first ring bursts into three arrows per orb after 0.8 seconds
+    game.ticks(26);
+    assert_eq!(orbs(game.world()), 20);
+    assert_eq!(arrows(game.world()), 30);
+}
+
+#[test]
+fn light_bullets_hit_and_graze_like_entity_bullets() {
+    let mut game = TestGame::with_seed(13);
+    game.enter_game();
+
+    let player = game.player();
+    let transform = *game.world().get::<Transform>(player).unwrap();
+    let position = transform.translation.xy();
+    let grazed = |game: &TestGame| game.world().resource::<GrazeMeter>().total();
+    let start = grazed(&game);
+
+    let world = game.app().world_mut();
+    world.entity_mut(player).insert(Invincible);
+    world
+        .resource_mut::<LightBullets>()
+        .push(LightBullet::enemy(position, Vec2::ZERO));
+    game.ticks(4);
+    assert!(game.world().resource::<LightBullets>().is_empty());
+    assert!(game.player_shield().unwrap().is_full());
+    assert_eq!(grazed(&game), start + 1);
+
+    let orb = game
+        .app()
+        .world_mut()
+        .spawn((RedOrb, Transform::from_translation(transform.translation)))
+        .id();
+    game.ticks(4);
+    assert!(game.world().get_entity(orb).is_err());
+    assert!(game.player_shield().unwrap().is_full());
+    assert_eq!(grazed(&game), start + 2);
+
+    let world = game.app().world_mut();
+    world.entity_mut(player).remove::<Invincible>();
+    world
+        .resource_mut::<LightBullets>()
+        .push(LightBullet::enemy(position, Vec2::ZERO));
+    game.ticks(4);
+    assert_eq!(game.player_shield().unwrap().current(), PLAYER_SHIELD - 1.);
+    assert_eq!(grazed(&game), start + 3);
+}
+
+#[test]
+fn light_bullets_pass_through_disabled_colliders() {
+    let mut game = TestGame::with_seed(31);
+    game.enter_game();
+
+    let player = game.player();
+    let position = game
+        .world()
+        .get::<Transform>(player)
+        .unwrap()
+        .translation
+        .xy();
+    let world = game.app().world_mut();
+    world.entity_mut(player).insert(ColliderDisabled);
+    world
+        .resource_mut::<LightBullets>()
+        .push(LightBullet::enemy(position, Vec2::ZERO));
+    game.ticks(4);
+    assert!(!game.world().resource::<LightBullets>().is_empty());
+    assert!(game.player_shield().unwrap().is_full());
+}
+
+#[test]
+fn bullets_are_the_source_of_their_damage() {
+    let mut game = TestGame::with_seed(27);
+    game.enter_game();
+
+    let player = game.player();
+    let translation = game.world().get::<Transform>(player).unwrap().translation;
+    let orb = game
+        .app()
+        .world_mut()
+        .spawn((RedOrb, Transform::from_translation(translation)))
+        .id();
+
+    game.run_until(8, |world| {
+        world
+            .resource::<Events<DamageDealt>>()
+            .iter_current_update_events()
+            .any(|dealt| dealt.entity == player && dealt.source == Some(orb))
+    })
+    .expect("the orb never hit the player");
+}
+
+#[test]
+fn hit_flashes_restore_sprite_colours() {
+    let mut game = TestGame::with_seed(28);
+    game.enter_game();
+
+    let red = Color::srgb(1., 0., 0.);
+    let enemy = game
+        .app()
+        .world_mut()
+        .spawn((Enemy, Health::full(10.), Sprite::from_color(red, Vec2::ONE)))
+        .id();
+    let color = |game: &TestGame| game.world().get::<Sprite>(enemy).unwrap().color;
+
+    game.app()
+        .world_mut()
+        .send_event(DamageEvent::new(enemy, 1.));
+    game.ticks(2);
+    assert_ne!(color(&game), red);
+    game.ticks(16);
+    assert_eq!(color(&game), red);
+}
+
+fn player_x(game: &mut TestGame) -> f32 {
+    let player = game.player();
+    game.world().get::<Transform>(player).unwrap().translation.x
+}